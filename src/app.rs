@@ -1,45 +1,212 @@
 use std::cell::RefCell;
+use std::fs::File;
+use std::io::BufWriter;
 use std::ops::Deref;
 use std::rc::Rc;
-use crate::maze::Maze;
+use std::time::{Duration, Instant};
+use pathfinder::cell::{MUD_COST, WATER_COST};
+use pathfinder::direction::Direction;
+use pathfinder::generator::{Generation, Generator};
+use pathfinder::history::History;
+use pathfinder::maze::Maze;
+use pathfinder::race::Race;
+use pathfinder::viewport::{Camera, Viewport};
+use crate::cast::CastRecorder;
 use color_eyre::eyre::WrapErr;
 use ratatui::crossterm::event;
-use ratatui::crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
-use ratatui::widgets::{Block, Borders};
+use ratatui::crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::widgets::{Block, Borders, Clear};
 use ratatui::{
     style::{Color, Stylize},
     prelude::*,
-    widgets::canvas::{Canvas, Line},
+    widgets::canvas::{Canvas, Context, Line},
 };
 use ratatui::{DefaultTerminal, Frame};
-use ratatui::widgets::canvas::Rectangle;
-use crate::right_hand::RightHand;
+use ratatui::widgets::canvas::{Points, Rectangle};
+use pathfinder::solver;
+use pathfinder::solver::{step_budget, SolveOutcome, Solver, SolverOptions};
+
+/// Slowest and fastest speeds of the automatic mode, in steps per second.
+const MIN_SPEED: u32 = 1;
+const MAX_SPEED: u32 = 512;
+
+/// Colours given to the sets of connected cells while a generation is animated.
+const SET_COLORS: [Color; 6] = [Color::Red, Color::Green, Color::Yellow, Color::Blue, Color::Magenta, Color::Cyan];
+
+/// What is drawn on top of the maze walls.
+pub enum View<'a> {
+    Solving(&'a dyn Solver),
+    Generating(&'a dyn Generator),
+}
 
 #[derive(Debug)]
 pub struct App {
     exit: bool,
     pub maze: Rc<RefCell<Maze>>,
-    pub solver : RightHand,
+    pub solver: Box<dyn Solver>,
+    pub options: SolverOptions,
+    history: History,
+    /// Generation being animated, the solver only starts once it is finished.
+    generation: Option<Generation>,
+    /// Solvers racing each other, shown instead of the single solver.
+    race: Option<Race>,
+    /// Whether the automatic mode is stepping the solver or the generation.
+    running: bool,
+    /// Steps per second of the automatic mode.
+    speed: u32,
+    last_tick: Instant,
+    /// Recording of the session, written every time a new frame is drawn.
+    recorder: Option<CastRecorder<BufWriter<File>>>,
+    /// Zoom and position of the part of the maze that is drawn.
+    camera: Camera,
+    /// Area of the last frame, to turn mouse positions into cells.
+    area: Rect,
 }
 
 impl App {
 
-    pub fn new(maze: Rc<RefCell<Maze>>) -> Self {
+    pub fn new(maze: Rc<RefCell<Maze>>, mut solver: Box<dyn Solver>, options: SolverOptions) -> Self {
+        solver.reset();
+        let history = History::new(solver.as_ref());
         Self {
             exit: false,
-            maze: maze.clone(),
-            solver: RightHand::new(maze.clone()),
+            maze,
+            solver,
+            options,
+            history,
+            generation: None,
+            race: None,
+            running: false,
+            speed: 8,
+            last_tick: Instant::now(),
+            recorder: None,
+            camera: Camera::default(),
+            area: Rect::default(),
+        }
+    }
+
+    /// Shows `generation` building the maze step by step before solving it.
+    pub fn animate_generation(mut self, generation: Generation) -> Self {
+        self.generation = Some(generation);
+        self
+    }
+
+    /// Races the solvers of `race` side by side instead of running the single solver.
+    pub fn race(mut self, race: Race) -> Self {
+        self.race = Some(race);
+        self
+    }
+
+    /// Records every frame drawn with `recorder`.
+    pub fn record(mut self, recorder: CastRecorder<BufWriter<File>>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    fn tick_rate(&self) -> Duration {
+        Duration::from_secs(1) / self.speed
+    }
+
+    /// Replaces the current solver by the one named `name` and restarts the resolution.
+    fn load_solver(&mut self, name: &str) {
+        if let Some(mut next) = solver::create(name, self.maze.clone(), &self.options) {
+            next.reset();
+            self.history = History::new(next.as_ref());
+            self.solver = next;
+            self.running = false;
         }
     }
 
     pub fn run(&mut self, terminal: &mut DefaultTerminal) -> color_eyre::Result<()> {
         while !self.exit {
-            terminal.draw(|frame| self.draw(frame))?;
-            self.handle_events().wrap_err("Failed to handle events")?;
+            let frame = terminal.draw(|frame| self.draw(frame))?;
+            self.area = frame.area;
+            if let Some(recorder) = &mut self.recorder {
+                recorder.frame(frame.buffer).wrap_err("Failed to record the frame")?;
+            }
+
+            let timeout = self.tick_rate().saturating_sub(self.last_tick.elapsed());
+            if event::poll(timeout)? {
+                self.handle_events().wrap_err("Failed to handle events")?;
+            }
+
+            if self.last_tick.elapsed() >= self.tick_rate() {
+                self.on_tick();
+                self.last_tick = Instant::now();
+            }
         }
         Ok(())
     }
 
+    /// Advances the automatic mode by one step, stopping it once the solver is done.
+    fn on_tick(&mut self) {
+        if !self.running {
+            return;
+        }
+
+        if self.generation.is_some() {
+            self.generation_step();
+            return;
+        }
+
+        if let Some(race) = self.race.as_mut() {
+            race.step();
+            self.running = !race.is_finished();
+            return;
+        }
+
+        self.history.step(self.solver.as_mut());
+        if self.outcome().is_some() {
+            self.running = false;
+        }
+    }
+
+    /// How the current run ended, stopping it once it took more steps than the budget.
+    fn outcome(&self) -> Option<SolveOutcome> {
+        let budget = step_budget(&self.maze.borrow());
+        self.solver.outcome().or((self.history.cursor() >= budget).then_some(SolveOutcome::BudgetExceeded))
+    }
+
+    /// Cell followed by the camera: the last carved cell while generating, the current cell of the solver otherwise.
+    fn focus(&self) -> (usize, usize) {
+        if self.generation.is_some() {
+            return self.maze.borrow().last_carved.unwrap_or((0, 0));
+        }
+        match &self.race {
+            Some(race) => race.racers()[0].solver.current_cell(),
+            None => self.solver.current_cell(),
+        }
+    }
+
+    /// Cells drawn in the last frame, or in the first pane of a race.
+    fn viewport(&self) -> Viewport {
+        self.camera.viewport(&self.maze.borrow(), dots(self.area), self.focus())
+    }
+
+    fn generation_step(&mut self) {
+        let Some(generation) = self.generation.as_mut() else {
+            return;
+        };
+
+        generation.step(&mut self.maze.borrow_mut());
+        if generation.is_finished() {
+            self.end_generation();
+        }
+    }
+
+    /// Completes the animated generation and hands the maze over to the solver.
+    fn end_generation(&mut self) {
+        if let Some(mut generation) = self.generation.take() {
+            generation.finish(&mut self.maze.borrow_mut());
+        }
+        self.solver.reset();
+        self.history = History::new(self.solver.as_ref());
+        if let Some(race) = self.race.as_mut() {
+            *race = Race::new(&race.names(), &self.maze.borrow(), &self.options).unwrap();
+        }
+        self.running = false;
+    }
 
     pub fn draw(&self, frame: &mut Frame) {
         let area = frame.area();
@@ -50,7 +217,136 @@ impl App {
 
         frame.render_widget(block, area);
 
-        draw_maze(self.maze.borrow().deref(), area, frame.buffer_mut());
+        let state = if self.running { "Running" } else { "Paused" };
+
+        if let Some(generation) = &self.generation {
+            let instructions = text::Line::from(vec![
+                " Step ".into(),
+                "<Right>".blue().bold(),
+                " Skip ".into(),
+                "<End>".blue().bold(),
+                " Automatic ".into(),
+                "<A>".blue().bold(),
+                " Speed ".into(),
+                "<+/->".blue().bold(),
+                " Pan ".into(),
+                "<Shift+Arrows>".blue().bold(),
+                " Zoom ".into(),
+                "<I/O>".blue().bold(),
+                " Follow ".into(),
+                "<F>".blue().bold(),
+                " Quit ".into(),
+                " <Ctrl-Q> ".blue().bold(),
+                format!("| {} at {} steps/s ", state, self.speed).into(),
+            ]);
+            let view = View::Generating(generation.generator());
+            draw_maze(self.maze.borrow().deref(), view, &self.camera, instructions, area, frame.buffer_mut());
+            return;
+        }
+
+        if let Some(race) = &self.race {
+            self.draw_race(race, frame);
+            return;
+        }
+
+        let mut instructions = text::Line::from(vec![
+            " Step ".into(),
+            "<Left/Right>".blue().bold(),
+            " Start/End ".into(),
+            "<Home/End>".blue().bold(),
+            " Automatic ".into(),
+            "<A>".blue().bold(),
+            " Speed ".into(),
+            "<+/->".blue().bold(),
+            " Switch Solver ".into(),
+            "<Tab>".blue().bold(),
+            " Heuristic ".into(),
+            "<H>".blue().bold(),
+            " Pan ".into(),
+            "<Shift+Arrows>".blue().bold(),
+            " Zoom ".into(),
+            "<I/O>".blue().bold(),
+            " Follow ".into(),
+            "<F>".blue().bold(),
+            " Quit ".into(),
+            " <Ctrl-Q> ".blue().bold(),
+            format!("| {} at {} steps/s ", state, self.speed).into(),
+            format!("| Step {}/{} ", self.history.cursor(), self.history.len()).into(),
+        ]);
+        if let Some(direction) = self.history.current().and_then(|record| record.direction) {
+            instructions.push_span(format!("facing {:?} ", direction));
+        }
+        if let Some(outcome) = self.outcome() {
+            let description = format!("| {} ", outcome.description());
+            match outcome {
+                SolveOutcome::Reached => instructions.push_span(description.green().bold()),
+                _ => instructions.push_span(description.red().bold()),
+            }
+        }
+
+        draw_maze(self.maze.borrow().deref(), View::Solving(self.solver.as_ref()), &self.camera, instructions, area, frame.buffer_mut());
+    }
+
+    /// Draws one pane per racer, with the controls of the race below them.
+    fn draw_race(&self, race: &Race, frame: &mut Frame) {
+        let [panes, controls] = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+
+        // Two panes per row at most, so that the mazes stay readable
+        let racers = race.racers().len();
+        let rows = Layout::vertical(vec![Constraint::Fill(1); racers.div_ceil(2)]).split(panes);
+        let areas: Vec<Rect> = rows
+            .iter()
+            .enumerate()
+            .flat_map(|(row, &area)| {
+                let columns = (racers - row * 2).min(2);
+                Layout::horizontal(vec![Constraint::Fill(1); columns]).split(area).to_vec()
+            })
+            .collect();
+
+        let winners = race.winners();
+        for (index, (racer, area)) in race.racers().iter().zip(areas).enumerate() {
+            let path = racer.path_length().map_or("-".to_string(), |length| length.to_string());
+            let mut counters = text::Line::from(format!(
+                " Steps {} | Visited {} | Path {} ",
+                racer.steps(),
+                racer.visited(),
+                path,
+            ));
+            if winners.contains(&index) {
+                counters.push_span(" Winner ".black().on_green().bold());
+            } else if let Some(outcome) = race.outcome(index) {
+                let description = format!("| {} ", outcome.description());
+                match outcome {
+                    SolveOutcome::Reached => counters.push_span(description.green().bold()),
+                    _ => counters.push_span(description.red().bold()),
+                }
+            }
+
+            let maze = racer.solver.maze().borrow();
+            draw_maze(maze.deref(), View::Solving(racer.solver.as_ref()), &self.camera, counters, area, frame.buffer_mut());
+        }
+
+        let state = if self.running { "Running" } else { "Paused" };
+        let instructions = text::Line::from(vec![
+            " Step ".into(),
+            "<Right>".blue().bold(),
+            " Start/End ".into(),
+            "<Home/End>".blue().bold(),
+            " Automatic ".into(),
+            "<A>".blue().bold(),
+            " Speed ".into(),
+            "<+/->".blue().bold(),
+            " Pan ".into(),
+            "<Shift+Arrows>".blue().bold(),
+            " Zoom ".into(),
+            "<I/O>".blue().bold(),
+            " Follow ".into(),
+            "<F>".blue().bold(),
+            " Quit ".into(),
+            " <Ctrl-Q> ".blue().bold(),
+            format!("| {} at {} steps/s ", state, self.speed).into(),
+        ]);
+        frame.render_widget(instructions.centered(), controls);
     }
 
     fn handle_events(&mut self) -> color_eyre::Result<()> {
@@ -58,20 +354,156 @@ impl App {
             Event::Key(key_event) if key_event.kind == KeyEventKind::Press => self
                 .handle_key_events(key_event)
                 .wrap_err_with(|| format!("Failed to handle key events: {:#?}", key_event)),
+            Event::Mouse(mouse_event) => {
+                self.handle_mouse_event(mouse_event);
+                Ok(())
+            },
             _ => Ok(())
         }
     }
 
     fn handle_key_events(&mut self, key: KeyEvent) -> color_eyre::Result<()> {
+        if self.handle_camera_key_events(key) {
+            return Ok(());
+        }
+        if self.generation.is_some() {
+            return self.handle_generation_key_events(key);
+        }
+        if self.race.is_some() {
+            return self.handle_race_key_events(key);
+        }
+
+        match key.code {
+            KeyCode::Char('q') if key.modifiers == KeyModifiers::CONTROL => {
+                self.exit = true;
+            },
+            KeyCode::Char('a') | KeyCode::Char(' ') => {
+                self.running = !self.running && self.outcome().is_none();
+            },
+            KeyCode::End => {
+                let budget = step_budget(&self.maze.borrow());
+                self.history.end(self.solver.as_mut(), budget);
+                self.running = false;
+            },
+            KeyCode::Home => {
+                self.history.start(&mut self.solver);
+                self.running = false;
+            },
+            KeyCode::Char('+') | KeyCode::Up => {
+                self.speed = (self.speed * 2).min(MAX_SPEED);
+            },
+            KeyCode::Char('-') | KeyCode::Down => {
+                self.speed = (self.speed / 2).max(MIN_SPEED);
+            },
+            KeyCode::Right if self.outcome().is_none() => {
+                self.history.step(self.solver.as_mut());
+            },
+            KeyCode::Left => {
+                self.history.back(&mut self.solver);
+                self.running = false;
+            },
+            KeyCode::Tab => {
+                self.load_solver(solver::next_name(self.solver.name()));
+            },
+            KeyCode::Char('h') => {
+                self.options.heuristic = self.options.heuristic.next();
+                self.load_solver(self.solver.name());
+            },
+            _ => ()
+        }
+        Ok(())
+    }
+
+    /// Pans, zooms or follows with the camera keys, shared by every mode. Returns whether `key` was one of them.
+    fn handle_camera_key_events(&mut self, key: KeyEvent) -> bool {
+        let viewport = self.viewport();
+        // Rows go up the screen, a quarter of the viewport at a time
+        let rows = (viewport.rows / 4).max(1) as isize;
+        let columns = (viewport.columns / 4).max(1) as isize;
+        let maze = self.maze.borrow();
+
+        match key.code {
+            KeyCode::Up if key.modifiers == KeyModifiers::SHIFT => self.camera.pan(&maze, viewport, (rows, 0)),
+            KeyCode::Down if key.modifiers == KeyModifiers::SHIFT => self.camera.pan(&maze, viewport, (-rows, 0)),
+            KeyCode::Left if key.modifiers == KeyModifiers::SHIFT => self.camera.pan(&maze, viewport, (0, -columns)),
+            KeyCode::Right if key.modifiers == KeyModifiers::SHIFT => self.camera.pan(&maze, viewport, (0, columns)),
+            KeyCode::Char('i') => self.camera.zoom_in(&maze, dots(self.area)),
+            KeyCode::Char('o') => self.camera.zoom_out(&maze, dots(self.area)),
+            KeyCode::Char('f') => self.camera.toggle_follow(self.focus()),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Zooms with the mouse wheel and centres the camera on the clicked cell.
+    fn handle_mouse_event(&mut self, mouse: MouseEvent) {
+        let dots = dots(self.area);
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.camera.zoom_in(&self.maze.borrow(), dots),
+            MouseEventKind::ScrollDown => self.camera.zoom_out(&self.maze.borrow(), dots),
+            // Race panes each have their own area, clicks are only mapped to cells in the single view
+            MouseEventKind::Down(MouseButton::Left) if self.race.is_none() => {
+                if let Some(cell) = cell_at(self.viewport(), self.area, (mouse.column, mouse.row)) {
+                    self.camera.center_on(cell);
+                }
+            },
+            _ => ()
+        }
+    }
+
+    fn handle_generation_key_events(&mut self, key: KeyEvent) -> color_eyre::Result<()> {
         match key.code {
             KeyCode::Char('q') if key.modifiers == KeyModifiers::CONTROL => {
                 self.exit = true;
             },
-            KeyCode::Char('a') => {
-                self.solver.automatic_execution();
+            KeyCode::Char('a') | KeyCode::Char(' ') => {
+                self.running = !self.running;
+            },
+            KeyCode::Char('+') | KeyCode::Up => {
+                self.speed = (self.speed * 2).min(MAX_SPEED);
+            },
+            KeyCode::Char('-') | KeyCode::Down => {
+                self.speed = (self.speed / 2).max(MIN_SPEED);
             },
             KeyCode::Right => {
-                self.solver.step();
+                self.generation_step();
+            },
+            KeyCode::End => {
+                self.end_generation();
+            },
+            _ => ()
+        }
+        Ok(())
+    }
+
+    fn handle_race_key_events(&mut self, key: KeyEvent) -> color_eyre::Result<()> {
+        let Some(race) = self.race.as_mut() else {
+            return Ok(());
+        };
+
+        match key.code {
+            KeyCode::Char('q') if key.modifiers == KeyModifiers::CONTROL => {
+                self.exit = true;
+            },
+            KeyCode::Char('a') | KeyCode::Char(' ') => {
+                self.running = !self.running && !race.is_finished();
+            },
+            KeyCode::Char('+') | KeyCode::Up => {
+                self.speed = (self.speed * 2).min(MAX_SPEED);
+            },
+            KeyCode::Char('-') | KeyCode::Down => {
+                self.speed = (self.speed / 2).max(MIN_SPEED);
+            },
+            KeyCode::Right => {
+                race.step();
+            },
+            KeyCode::End => {
+                race.end();
+                self.running = false;
+            },
+            KeyCode::Home => {
+                race.reset();
+                self.running = false;
             },
             _ => ()
         }
@@ -79,80 +511,270 @@ impl App {
     }
 }
 
-pub fn draw_maze(maze: &Maze, area: Rect, buf: &mut Buffer) {
-    let width = maze.width as f64;
-    let height = maze.height as f64;
+/// Braille dots the maze is drawn with in `area`, as (columns, rows).
+fn dots(area: Rect) -> (usize, usize) {
+    let inner = Block::default().borders(Borders::ALL).inner(area);
+    (inner.width as usize * 2, inner.height as usize * 4)
+}
 
-    let instructions = text::Line::from(vec![
-        " One Iteration ".into(),
-        "<Right>".blue().bold(),
-        " Automatic ".into(),
-        "<A>".blue().bold(),
-        " Quit ".into(),
-        " <Ctrl-Q> ".blue().bold(),
-    ]);
+/// Cell of `viewport` drawn at the terminal `position` of `area`, if any.
+fn cell_at(viewport: Viewport, area: Rect, position: (u16, u16)) -> Option<(usize, usize)> {
+    let inner = Block::default().borders(Borders::ALL).inner(area);
+    if !inner.contains(Position::new(position.0, position.1)) {
+        return None;
+    }
+
+    // Rows go up the screen, from the bottom of the area
+    let column = (position.0 - inner.x) as usize * viewport.columns / inner.width as usize;
+    let row = (inner.bottom() - 1 - position.1) as usize * viewport.rows / inner.height as usize;
+    Some((viewport.top + row, viewport.left + column))
+}
+
+pub fn draw_maze(maze: &Maze, view: View, camera: &Camera, instructions: text::Line, area: Rect, buf: &mut Buffer) {
+    let focus = match view {
+        View::Solving(solver) => solver.current_cell(),
+        View::Generating(_) => maze.last_carved.unwrap_or((0, 0)),
+    };
+    let viewport = camera.viewport(maze, dots(area), focus);
+
+    let title = match view {
+        View::Solving(solver) => {
+            let mut title = match (maze.generator, maze.seed) {
+                (Some(generator), Some(seed)) => format!("Maze {} {} - {}", generator, seed, solver.label()),
+                _ => format!("Maze - {}", solver.label()),
+            };
+            if let Some((g, f)) = solver.scores(solver.current_cell()) {
+                title.push_str(&format!(" - g: {} f: {:.1}", g, f));
+            }
+            title
+        },
+        View::Generating(generator) => match maze.seed {
+            Some(seed) => format!("Generating with {} - {}", generator.name(), seed),
+            None => format!("Generating with {}", generator.name()),
+        },
+    };
 
     let canvas = Canvas::default()
-        .block(Block::default().title("Maze").borders(Borders::ALL).title_bottom(instructions.centered()))
-        .x_bounds([0.0, width])
-        .y_bounds([0.0, height])
+        .block(Block::default().title(title).borders(Borders::ALL).title_bottom(instructions.centered()))
+        .x_bounds([viewport.left as f64, viewport.right() as f64])
+        .y_bounds([viewport.top as f64, viewport.bottom() as f64])
         .paint(|ctx| {
-            let current_cell = maze.current_cell;
-            let exit_cell = (maze.height - 1, maze.width - 1);
+            for (i, j) in viewport.cells() {
+                let cell = maze.cell((i, j));
+                let x = j as f64;
+                let y = i as f64;
+
+                if let Some(color) = cost_color(cell.cost) {
+                    ctx.draw(&Rectangle {
+                        x: x + 0.25,
+                        y: y + 0.25,
+                        width: 0.5,
+                        height: 0.5,
+                        color,
+                    });
+                }
 
-            for (i, row) in maze.cells.iter().enumerate() {
-                for (j, cell) in row.iter().enumerate() {
-                    let x = j as f64;
-                    let y = i as f64;
+                if cell.visited {
+                    ctx.draw(&Rectangle {
+                        x,
+                        y,
+                        width: 1.0,
+                        height: 1.0,
+                        color: Color::DarkGray,
+                    });
+                }
+
+                if cell.filled {
+                    ctx.draw(&Rectangle {
+                        x,
+                        y,
+                        width: 1.0,
+                        height: 1.0,
+                        color: Color::Red,
+                    });
+                }
 
-                    if cell.visited {
+                if let View::Generating(generator) = view {
+                    if let Some(set) = generator.set_of((i, j)) {
                         ctx.draw(&Rectangle {
-                            x,
-                            y,
-                            width: 1.0,
-                            height: 1.0,
-                            color: Color::DarkGray,
+                            x: x + 0.25,
+                            y: y + 0.25,
+                            width: 0.5,
+                            height: 0.5,
+                            color: SET_COLORS[set % SET_COLORS.len()],
                         });
                     }
+                }
 
-                    if cell.top_wall {
-                        ctx.draw(&Line { x1: x, y1: y, x2: x + 1.0, y2: y, color: Color::White });
-                    }
-                    if cell.bottom_wall {
-                        ctx.draw(&Line { x1: x, y1: y + 1.0, x2: x + 1.0, y2: y + 1.0, color: Color::White });
-                    }
-                    if cell.left_wall {
-                        ctx.draw(&Line { x1: x, y1: y, x2: x, y2: y + 1.0, color: Color::White });
-                    }
-                    if cell.right_wall {
-                        ctx.draw(&Line { x1: x + 1.0, y1: y, x2: x + 1.0, y2: y + 1.0, color: Color::White });
-                    }
+                if cell.top_wall {
+                    ctx.draw(&Line { x1: x, y1: y, x2: x + 1.0, y2: y, color: Color::White });
+                }
+                if cell.bottom_wall {
+                    ctx.draw(&Line { x1: x, y1: y + 1.0, x2: x + 1.0, y2: y + 1.0, color: Color::White });
+                }
+                if cell.left_wall {
+                    ctx.draw(&Line { x1: x, y1: y, x2: x, y2: y + 1.0, color: Color::White });
+                }
+                if cell.right_wall {
+                    ctx.draw(&Line { x1: x + 1.0, y1: y, x2: x + 1.0, y2: y + 1.0, color: Color::White });
                 }
             }
 
-            ctx.print(0.5, 0.5, "S".green());
-
-            if current_cell == exit_cell {
-                ctx.print(
-                    current_cell.1 as f64 + 0.5,
-                    current_cell.0 as f64 + 0.5,
-                    "PE".blue(),
-                )
-            } else {
-                ctx.print(
-                    current_cell.1 as f64 + 0.5,
-                    current_cell.0 as f64 + 0.5,
-                    "P".yellow(),
-                );
-
-                ctx.print(
-                    exit_cell.1 as f64 + 0.5,
-                    exit_cell.0 as f64 + 0.5,
-                    "E".red(),
-                );
+            match view {
+                View::Solving(solver) => draw_solver(ctx, maze, solver, viewport),
+                View::Generating(generator) => draw_generator(ctx, maze, generator, viewport),
             }
         });
 
     canvas.render(area, buf);
+
+    if viewport != Viewport::whole(maze) {
+        draw_minimap(maze, camera, viewport, focus, area, buf);
+    }
 }
 
+/// Draws the whole maze in small in the bottom right corner of `area`, with the outline of
+/// `viewport` and the followed cell, so that the drawn part can be placed in the maze.
+fn draw_minimap(maze: &Maze, camera: &Camera, viewport: Viewport, focus: (usize, usize), area: Rect, buf: &mut Buffer) {
+    let inner = Block::default().borders(Borders::ALL).inner(area);
+
+    // Braille dots are about square, so the map keeps the proportions of the maze
+    let width = (inner.width / 4).min(32);
+    let height = (width as usize * maze.height / (maze.width * 2)) as u16 + 2;
+    let height = height.clamp(3, inner.height / 3);
+    if width < 8 || height < 3 {
+        return;
+    }
+
+    let title = if camera.follow { "Map - following" } else { "Map" };
+    let map = Rect::new(inner.right() - width, inner.bottom() - height, width, height);
+    let (width, height) = (maze.width as f64, maze.height as f64);
+    let canvas = Canvas::default()
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .x_bounds([0.0, width])
+        .y_bounds([0.0, height])
+        .paint(|ctx| {
+            ctx.draw(&Rectangle {
+                x: viewport.left as f64,
+                y: viewport.top as f64,
+                width: viewport.columns as f64,
+                height: viewport.rows as f64,
+                color: Color::Yellow,
+            });
+            for (cell, color) in [(maze.start, Color::Green), (maze.exit, Color::Red), (focus, Color::Yellow)] {
+                ctx.draw(&Points { coords: &[(cell.1 as f64 + 0.5, cell.0 as f64 + 0.5)], color });
+            }
+        });
+
+    Clear.render(map, buf);
+    canvas.render(map, buf);
+}
+
+fn draw_solver(ctx: &mut Context, maze: &Maze, solver: &dyn Solver, viewport: Viewport) {
+    let current_cell = solver.current_cell();
+    let start_cell = maze.start;
+    let exit_cell = maze.exit;
+
+    for (row, col) in viewport.cells().filter(|&cell| solver.in_frontier(cell)) {
+        ctx.draw(&Rectangle {
+            x: col as f64,
+            y: row as f64,
+            width: 1.0,
+            height: 1.0,
+            color: Color::Cyan,
+        });
+    }
+
+    for (row, col) in solver.path().into_iter().filter(|&cell| viewport.contains(cell)) {
+        ctx.draw(&Rectangle {
+            x: col as f64,
+            y: row as f64,
+            width: 1.0,
+            height: 1.0,
+            color: Color::LightGreen,
+        });
+    }
+
+    // Each passage is drawn from the cell west or north of it, the ones on the
+    // viewport edges also from the cell inside the viewport
+    for cell in viewport.cells() {
+        let directions = [
+            (Direction::East, true),
+            (Direction::South, true),
+            (Direction::North, cell.0 == viewport.top),
+            (Direction::West, cell.1 == viewport.left),
+        ];
+        for (direction, _) in directions.into_iter().filter(|&(_, drawn)| drawn) {
+            if let Some(next) = direction.step_from(maze, cell) {
+                draw_passage_marks(ctx, cell, next, solver.marks(cell, next));
+            }
+        }
+    }
+
+    ctx.print(start_cell.1 as f64 + 0.5, start_cell.0 as f64 + 0.5, "S".green());
+
+    if current_cell == exit_cell {
+        ctx.print(
+            current_cell.1 as f64 + 0.5,
+            current_cell.0 as f64 + 0.5,
+            "PE".blue(),
+        )
+    } else {
+        ctx.print(
+            current_cell.1 as f64 + 0.5,
+            current_cell.0 as f64 + 0.5,
+            "P".yellow(),
+        );
+
+        ctx.print(
+            exit_cell.1 as f64 + 0.5,
+            exit_cell.0 as f64 + 0.5,
+            "E".red(),
+        );
+    }
+}
+
+/// Draws a tick across the passage between two adjacent cells, yellow when it was
+/// walked through once and red when it was walked through twice.
+fn draw_passage_marks(ctx: &mut Context, first: (usize, usize), second: (usize, usize), marks: u8) {
+    let color = match marks {
+        0 => return,
+        1 => Color::Yellow,
+        _ => Color::Red,
+    };
+
+    let (row, col) = (first.0.max(second.0) as f64, first.1.max(second.1) as f64);
+    if first.0 == second.0 {
+        ctx.draw(&Line { x1: col, y1: row + 0.3, x2: col, y2: row + 0.7, color });
+    } else {
+        ctx.draw(&Line { x1: col + 0.3, y1: row, x2: col + 0.7, y2: row, color });
+    }
+}
+
+/// Highlights the cells the generator works on and the last cell it carved.
+fn draw_generator(ctx: &mut Context, maze: &Maze, generator: &dyn Generator, viewport: Viewport) {
+    for (row, col) in viewport.cells().filter(|&cell| generator.is_active(cell)) {
+        ctx.draw(&Rectangle {
+            x: col as f64,
+            y: row as f64,
+            width: 1.0,
+            height: 1.0,
+            color: Color::Magenta,
+        });
+    }
+
+    if let Some((row, col)) = maze.last_carved {
+        ctx.print(col as f64 + 0.5, row as f64 + 0.5, "*".yellow());
+    }
+}
+
+/// Shading of a cell according to its traversal cost, `None` for plain cells.
+fn cost_color(cost: u32) -> Option<Color> {
+    if cost >= WATER_COST {
+        Some(Color::Blue)
+    } else if cost >= MUD_COST {
+        Some(Color::Rgb(139, 90, 43))
+    } else {
+        None
+    }
+}
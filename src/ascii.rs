@@ -0,0 +1,269 @@
+//! Plain-text maze format, drawn with ASCII characters:
+//!
+//! ```text
+//! +--+--+--+
+//! |S       |
+//! +--+--+  +
+//! |E  ~~ ..|
+//! +--+--+--+
+//! ```
+//!
+//! Every cell is two characters wide, between `+` corners. Walls are drawn with `--`
+//! and `|`, and open passages with spaces. Inside a cell, `S` marks the start and `E`
+//! the exit, which default to the top-left and bottom-right cells when missing.
+//! Cells covered with mud are filled with `..` and cells covered with water with `~~`.
+
+use std::fmt;
+use crate::cell::{DEFAULT_COST, MUD_COST, WATER_COST};
+use crate::direction::Direction;
+use crate::maze::Maze;
+
+/// Error found while reading a maze, at a 1-based line and column of the text.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        ParseError { line, column, message: message.into() }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Characters filling a cell covered with terrain of the given cost.
+fn terrain(cost: u32) -> &'static str {
+    if cost >= WATER_COST {
+        "~~"
+    } else if cost >= MUD_COST {
+        ".."
+    } else {
+        "  "
+    }
+}
+
+/// Writes `maze` in the plain-text format.
+pub fn to_ascii(maze: &Maze) -> String {
+    let mut text = String::new();
+
+    for i in 0..maze.height {
+        text.push('+');
+        for j in 0..maze.width {
+            text.push_str(if maze.has_wall((i, j), Direction::North) { "--" } else { "  " });
+            text.push('+');
+        }
+        text.push('\n');
+
+        for j in 0..maze.width {
+            let cell = maze.cell((i, j));
+            text.push(if cell.left_wall { '|' } else { ' ' });
+            let interior = terrain(cell.cost);
+            let marker = if (i, j) == maze.start {
+                "S"
+            } else if (i, j) == maze.exit {
+                "E"
+            } else {
+                &interior[..1]
+            };
+            text.push_str(marker);
+            text.push_str(&interior[1..]);
+        }
+        text.push(if maze.has_wall((i, maze.width - 1), Direction::East) { '|' } else { ' ' });
+        text.push('\n');
+    }
+
+    text.push('+');
+    for j in 0..maze.width {
+        text.push_str(if maze.has_wall((maze.height - 1, j), Direction::South) { "--" } else { "  " });
+        text.push('+');
+    }
+    text.push('\n');
+    text
+}
+
+/// Reads a maze written in the plain-text format.
+pub fn from_ascii(text: &str) -> Result<Maze, ParseError> {
+    let lines: Vec<Vec<char>> = text
+        .trim_end()
+        .lines()
+        .map(|line| line.trim_end_matches('\r').chars().collect())
+        .collect();
+
+    let first = lines.first().ok_or_else(|| ParseError::new(1, 1, "the maze is empty"))?;
+    if first.len() < 4 || (first.len() - 1) % 3 != 0 {
+        return Err(ParseError::new(1, first.len() + 1, "expected a line of cells like +--+--+"));
+    }
+    if lines.len() < 3 || lines.len().is_multiple_of(2) {
+        return Err(ParseError::new(lines.len() + 1, 1, "expected a line of walls like +--+--+"));
+    }
+
+    let width = (first.len() - 1) / 3;
+    let height = (lines.len() - 1) / 2;
+    let mut maze = Maze::new(width, height);
+    let mut start = None;
+    let mut exit = None;
+
+    for (index, line) in lines.iter().enumerate() {
+        let number = index + 1;
+        if line.len() != first.len() {
+            return Err(ParseError::new(
+                number,
+                line.len().min(first.len()) + 1,
+                format!("expected {} characters like the first line", first.len()),
+            ));
+        }
+
+        // Row of the cells below the line of walls, or of the cells of the line
+        let row = index / 2;
+        for j in 0..width {
+            let column = 3 * j;
+            if index % 2 == 0 {
+                if line[column] != '+' {
+                    return Err(ParseError::new(number, column + 1, "expected '+'"));
+                }
+                let wall = match (line[column + 1], line[column + 2]) {
+                    ('-', '-') => true,
+                    (' ', ' ') => false,
+                    _ => return Err(ParseError::new(number, column + 2, "expected '--' or two spaces")),
+                };
+                if row < height {
+                    maze.set_wall((row, j), Direction::North, wall);
+                } else {
+                    maze.set_wall((row - 1, j), Direction::South, wall);
+                }
+            } else {
+                let wall = match line[column] {
+                    '|' => true,
+                    ' ' => false,
+                    _ => return Err(ParseError::new(number, column + 1, "expected '|' or a space")),
+                };
+                maze.set_wall((row, j), Direction::West, wall);
+
+                let cost = match line[column + 2] {
+                    ' ' => DEFAULT_COST,
+                    '.' => MUD_COST,
+                    '~' => WATER_COST,
+                    _ => return Err(ParseError::new(number, column + 3, "expected ' ', '.' or '~'")),
+                };
+                maze.set_cost((row, j), cost);
+
+                let marker = match line[column + 1] {
+                    'S' => &mut start,
+                    'E' => &mut exit,
+                    character if character == line[column + 2] => continue,
+                    _ => return Err(ParseError::new(number, column + 2, "expected 'S', 'E' or the terrain of the cell")),
+                };
+                if marker.replace((row, j)).is_some() {
+                    return Err(ParseError::new(number, column + 2, format!("'{}' is found twice", line[column + 1])));
+                }
+            }
+        }
+
+        let last = first.len() - 1;
+        match (index % 2, line[last]) {
+            (0, '+') => (),
+            (0, _) => return Err(ParseError::new(number, last + 1, "expected '+'")),
+            (_, '|') => maze.set_wall((row, width - 1), Direction::East, true),
+            (_, ' ') => maze.set_wall((row, width - 1), Direction::East, false),
+            (_, _) => return Err(ParseError::new(number, last + 1, "expected '|' or a space")),
+        }
+    }
+
+    maze.start = start.unwrap_or(maze.start);
+    maze.exit = exit.unwrap_or(maze.exit);
+    maze.current_cell = maze.start;
+    Ok(maze)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::backtracker::Backtracker;
+
+    const TEST_MAZE: &str = "\
++--+--+--+
+|S       |
++--+--+  +
+|E  ~~ ..|
++--+--+--+
+";
+
+    #[test]
+    fn test_from_ascii() {
+        let maze = from_ascii(TEST_MAZE).unwrap();
+
+        assert_eq!((maze.width, maze.height), (3, 2));
+        assert_eq!(maze.start, (0, 0));
+        assert_eq!(maze.exit, (1, 0));
+        assert_eq!(maze.current_cell, (0, 0));
+        assert!(!maze.cell((0, 0)).right_wall && !maze.cell((0, 1)).left_wall);
+        assert!(!maze.cell((0, 2)).bottom_wall && !maze.cell((1, 2)).top_wall);
+        assert!(maze.cell((0, 0)).bottom_wall && maze.cell((1, 0)).top_wall);
+        assert!(maze.cell((1, 0)).left_wall && maze.cell((1, 2)).right_wall);
+        assert_eq!(maze.cell((1, 1)).cost, WATER_COST);
+        assert_eq!(maze.cell((1, 2)).cost, MUD_COST);
+        assert_eq!(maze.shortest_path_length(maze.start, maze.exit), Some(6));
+    }
+
+    #[test]
+    fn test_to_ascii() {
+        let maze = from_ascii(TEST_MAZE).unwrap();
+
+        assert_eq!(to_ascii(&maze), TEST_MAZE);
+    }
+
+    #[test]
+    fn test_ascii_round_trip() {
+        let mut maze = Maze::generate_weighted_with(&mut Backtracker::default(), 12, 7, 5);
+        maze.start = (3, 4);
+        maze.exit = (0, 11);
+        maze.reset_visited_cells();
+
+        let loaded = from_ascii(&to_ascii(&maze)).unwrap();
+
+        assert!(loaded.cells().eq(maze.cells()));
+        assert_eq!(loaded.start, maze.start);
+        assert_eq!(loaded.exit, maze.exit);
+    }
+
+    #[test]
+    fn test_from_ascii_default_start_and_exit() {
+        let maze = from_ascii("+--+--+\n|     |\n+--+--+\n").unwrap();
+
+        assert_eq!(maze.start, (0, 0));
+        assert_eq!(maze.exit, (0, 1));
+    }
+
+    #[test]
+    fn test_from_ascii_errors() {
+        let error = |text: &str| from_ascii(text).unwrap_err();
+
+        assert_eq!(error("").line, 1);
+        assert_eq!(error("+--+\n|  |\n").line, 3, "The last line of walls is missing");
+
+        let wrong_corner = error("+--+--+\n|     |\n+--*--+\n");
+        assert_eq!((wrong_corner.line, wrong_corner.column), (3, 4));
+
+        let wrong_wall = error("+--+--+\n|  #  |\n+--+--+\n");
+        assert_eq!((wrong_wall.line, wrong_wall.column), (2, 4));
+        assert_eq!(wrong_wall.to_string(), "line 2, column 4: expected '|' or a space");
+
+        let short_line = error("+--+--+\n|   |\n+--+--+\n");
+        assert_eq!((short_line.line, short_line.column), (2, 6));
+
+        let two_starts = error("+--+--+\n|S  S |\n+--+--+\n");
+        assert_eq!((two_starts.line, two_starts.column), (2, 5));
+
+        let unknown_terrain = error("+--+--+\n|   ??|\n+--+--+\n");
+        assert_eq!((unknown_terrain.line, unknown_terrain.column), (2, 6));
+    }
+}
@@ -0,0 +1,393 @@
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::rc::Rc;
+use crate::maze::{Maze, UNREACHED};
+use crate::solver::{Parents, SolveOutcome, Solver};
+
+/// Names of the heuristics accepted by `Heuristic::from_name`.
+pub const HEURISTICS: [&str; 3] = ["manhattan", "euclidean", "zero"];
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Heuristic {
+    #[default]
+    Manhattan,
+    Euclidean,
+    Zero,
+}
+
+impl Heuristic {
+    pub fn from_name(name: &str) -> Option<Heuristic> {
+        match name {
+            "manhattan" => Some(Heuristic::Manhattan),
+            "euclidean" => Some(Heuristic::Euclidean),
+            "zero" => Some(Heuristic::Zero),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Heuristic::Manhattan => "manhattan",
+            Heuristic::Euclidean => "euclidean",
+            Heuristic::Zero => "zero",
+        }
+    }
+
+    pub fn next(&self) -> Heuristic {
+        match self {
+            Heuristic::Manhattan => Heuristic::Euclidean,
+            Heuristic::Euclidean => Heuristic::Zero,
+            Heuristic::Zero => Heuristic::Manhattan,
+        }
+    }
+
+    /// Estimated distance between two cells, never greater than the real one.
+    pub fn estimate(&self, from: (usize, usize), to: (usize, usize)) -> f64 {
+        let rows = from.0.abs_diff(to.0) as f64;
+        let cols = from.1.abs_diff(to.1) as f64;
+
+        match self {
+            Heuristic::Manhattan => rows + cols,
+            Heuristic::Euclidean => (rows * rows + cols * cols).sqrt(),
+            Heuristic::Zero => 0.0,
+        }
+    }
+}
+
+/// Entry of the open set, ordered so that the `BinaryHeap` pops the lowest f score first.
+/// Ties are broken in favour of the cell closest to the exit.
+#[derive(Clone, Debug)]
+struct Node {
+    f: f64,
+    h: f64,
+    cell: (usize, usize),
+}
+
+impl PartialEq for Node {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Node {}
+
+impl PartialOrd for Node {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Node {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.f.total_cmp(&self.f)
+            .then_with(|| other.h.total_cmp(&self.h))
+            .then_with(|| other.cell.cmp(&self.cell))
+    }
+}
+
+/// Where a cell stands in the search.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Status {
+    New,
+    Open,
+    Closed,
+}
+
+/// Incremental A* search over the cell costs, closing one cell at each step.
+#[derive(Clone, Debug)]
+pub struct AStar {
+    pub maze: Rc<RefCell<Maze>>,
+    pub heuristic: Heuristic,
+    open: BinaryHeap<Node>,
+    /// Status of each cell, row by row.
+    statuses: Vec<Status>,
+    open_count: usize,
+    closed_count: usize,
+    /// g score of each cell, row by row, `UNREACHED` until the cell is reached.
+    g_scores: Vec<u32>,
+    parents: Parents,
+    finished: bool,
+}
+
+impl AStar {
+    pub fn new(maze: Rc<RefCell<Maze>>, heuristic: Heuristic) -> Self {
+        let (cells, parents) = {
+            let maze = maze.borrow();
+            (maze.width * maze.height, Parents::new(&maze))
+        };
+        let mut solver = AStar {
+            maze,
+            heuristic,
+            open: BinaryHeap::new(),
+            statuses: vec![Status::New; cells],
+            open_count: 0,
+            closed_count: 0,
+            g_scores: vec![UNREACHED; cells],
+            parents,
+            finished: false,
+        };
+        solver.push_start();
+        solver
+    }
+
+    pub fn is_open(&self, cell: (usize, usize)) -> bool {
+        self.statuses[self.index(cell)] == Status::Open
+    }
+
+    pub fn is_closed(&self, cell: (usize, usize)) -> bool {
+        self.statuses[self.index(cell)] == Status::Closed
+    }
+
+    /// Number of cells in the open set.
+    pub fn open_count(&self) -> usize {
+        self.open_count
+    }
+
+    /// Number of cells in the closed set.
+    pub fn closed_count(&self) -> usize {
+        self.closed_count
+    }
+
+    /// Cost of the best known path from the start to `cell`, summing the cost of the entered cells.
+    pub fn g_score(&self, cell: (usize, usize)) -> Option<usize> {
+        Some(self.g_scores[self.index(cell)]).filter(|&g| g != UNREACHED).map(|g| g as usize)
+    }
+
+    fn index(&self, cell: (usize, usize)) -> usize {
+        self.maze.borrow().index(cell)
+    }
+
+    /// g score plus the heuristic estimate from `cell` to the exit.
+    pub fn f_score(&self, cell: (usize, usize)) -> Option<f64> {
+        let exit = self.maze.borrow().exit;
+        self.g_score(cell).map(|g| g as f64 + self.heuristic.estimate(cell, exit))
+    }
+
+    fn push_start(&mut self) {
+        let (start, exit) = {
+            let maze = self.maze.borrow();
+            (maze.start, maze.exit)
+        };
+        let h = self.heuristic.estimate(start, exit);
+
+        let index = self.index(start);
+        self.open.push(Node { f: h, h, cell: start });
+        self.statuses[index] = Status::Open;
+        self.open_count = 1;
+        self.g_scores[index] = 0;
+    }
+
+    /// Pops the best open cell and closes it, skipping the stale entries left by score improvements.
+    fn pop_open(&mut self) -> Option<(usize, usize)> {
+        while let Some(node) = self.open.pop() {
+            let index = self.index(node.cell);
+            if self.statuses[index] == Status::Open {
+                self.statuses[index] = Status::Closed;
+                self.open_count -= 1;
+                self.closed_count += 1;
+                return Some(node.cell);
+            }
+        }
+        None
+    }
+}
+
+impl Solver for AStar {
+    fn name(&self) -> &'static str {
+        "astar"
+    }
+
+    fn label(&self) -> String {
+        format!(
+            "{} ({}) - open: {} closed: {}",
+            self.name(),
+            self.heuristic.name(),
+            self.open_count(),
+            self.closed_count(),
+        )
+    }
+
+    fn maze(&self) -> &Rc<RefCell<Maze>> {
+        &self.maze
+    }
+
+    fn step(&mut self) {
+        if self.finished {
+            return;
+        }
+
+        let Some(cell) = self.pop_open() else {
+            self.finished = true;
+            return;
+        };
+
+        let mut maze = self.maze.borrow_mut();
+        maze.current_cell = cell;
+        maze.set_visited(cell, true);
+
+        if cell == maze.exit {
+            self.finished = true;
+            return;
+        }
+
+        let g_current = self.g_scores[maze.index(cell)];
+        for neighbour in maze.accessible_neighbours(cell) {
+            let index = maze.index(neighbour);
+            if self.statuses[index] == Status::Closed {
+                continue;
+            }
+
+            let g = maze.cost_through(g_current, neighbour);
+            if self.g_scores[index] <= g {
+                continue;
+            }
+
+            let h = self.heuristic.estimate(neighbour, maze.exit);
+            self.g_scores[index] = g;
+            self.parents.insert(neighbour, cell);
+            self.open.push(Node { f: g as f64 + h, h, cell: neighbour });
+            if self.statuses[index] == Status::New {
+                self.statuses[index] = Status::Open;
+                self.open_count += 1;
+            }
+        }
+    }
+
+    fn outcome(&self) -> Option<SolveOutcome> {
+        if !self.finished {
+            return None;
+        }
+
+        let maze = self.maze.borrow();
+        if maze.current_cell == maze.exit {
+            Some(SolveOutcome::Reached)
+        } else {
+            Some(SolveOutcome::Unreachable)
+        }
+    }
+
+    fn reset(&mut self) {
+        {
+            let mut maze = self.maze.borrow_mut();
+            maze.reset_visited_cells();
+            maze.current_cell = maze.start;
+        }
+
+        self.open.clear();
+        self.statuses.fill(Status::New);
+        self.closed_count = 0;
+        self.g_scores.fill(UNREACHED);
+        self.parents.clear();
+        self.finished = false;
+        self.push_start();
+    }
+
+    fn frontier(&self) -> Vec<(usize, usize)> {
+        let mut cells: Vec<(usize, usize)> = self.open
+            .iter()
+            .map(|node| node.cell)
+            .filter(|&cell| self.is_open(cell))
+            .collect();
+        cells.sort_unstable();
+        cells.dedup();
+        cells
+    }
+
+    fn in_frontier(&self, cell: (usize, usize)) -> bool {
+        self.is_open(cell)
+    }
+
+    fn scores(&self, cell: (usize, usize)) -> Option<(usize, f64)> {
+        Some((self.g_score(cell)?, self.f_score(cell)?))
+    }
+
+    fn path(&self) -> Vec<(usize, usize)> {
+        let maze = self.maze.borrow();
+        if !self.finished || maze.current_cell != maze.exit {
+            return Vec::new();
+        }
+
+        self.parents.path_to(maze.exit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_maze() -> Rc<RefCell<Maze>> {
+        let mut maze = Maze::new(3, 3);
+
+        // Open grid without the walls around the centre cell, which is left isolated
+        maze.open_adjacent_wall((0, 0), (0, 1));
+        maze.open_adjacent_wall((0, 1), (0, 2));
+        maze.open_adjacent_wall((0, 2), (1, 2));
+        maze.open_adjacent_wall((1, 2), (2, 2));
+        maze.open_adjacent_wall((0, 0), (1, 0));
+        maze.open_adjacent_wall((1, 0), (2, 0));
+        maze.open_adjacent_wall((2, 0), (2, 1));
+        maze.open_adjacent_wall((2, 1), (2, 2));
+
+        Rc::new(RefCell::new(maze))
+    }
+
+    #[test]
+    fn test_heuristic_estimates() {
+        assert_eq!(Heuristic::Manhattan.estimate((0, 0), (3, 4)), 7.0);
+        assert_eq!(Heuristic::Euclidean.estimate((0, 0), (3, 4)), 5.0);
+        assert_eq!(Heuristic::Zero.estimate((0, 0), (3, 4)), 0.0);
+    }
+
+    #[test]
+    fn test_heuristic_names() {
+        for name in HEURISTICS {
+            let heuristic = Heuristic::from_name(name).expect("Every listed heuristic should be parsable");
+            assert_eq!(heuristic.name(), name);
+        }
+        assert_eq!(Heuristic::Zero.next(), Heuristic::Manhattan);
+    }
+
+    #[test]
+    fn test_astar_first_step() {
+        let maze = create_test_maze();
+        let mut solver = AStar::new(maze.clone(), Heuristic::Manhattan);
+
+        solver.step();
+
+        assert!(solver.is_closed((0, 0)));
+        assert_eq!(solver.open_count(), 2, "Both open neighbours should be queued");
+        assert!(solver.is_open((0, 1)) && solver.is_open((1, 0)));
+        assert_eq!(solver.g_score((0, 1)), Some(1));
+        assert_eq!(solver.f_score((0, 1)), Some(4.0));
+    }
+
+    #[test]
+    fn test_astar_shortest_path() {
+        for heuristic in [Heuristic::Manhattan, Heuristic::Euclidean, Heuristic::Zero] {
+            let maze = create_test_maze();
+            let mut solver = AStar::new(maze.clone(), heuristic);
+
+            solver.automatic_execution();
+
+            assert_eq!(maze.borrow().current_cell, (2, 2));
+            assert_eq!(solver.path().len(), 5, "The path should be 5 cells long with {:?}", heuristic);
+            assert_eq!(solver.g_score((2, 2)), Some(4));
+            assert!(!maze.borrow().cell((1, 1)).visited, "The isolated cell should never be closed");
+        }
+    }
+
+    #[test]
+    fn test_astar_reset() {
+        let maze = create_test_maze();
+        let mut solver = AStar::new(maze.clone(), Heuristic::Zero);
+
+        solver.automatic_execution();
+        solver.reset();
+
+        assert!(!solver.is_finished());
+        assert_eq!(solver.closed_count(), 0);
+        assert!(!solver.is_closed((2, 2)));
+        assert_eq!(solver.frontier(), vec![(0, 0)]);
+    }
+}
@@ -0,0 +1,341 @@
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use crate::generator;
+use crate::maze::Maze;
+use crate::solver::{self, step_budget, SolveOutcome, Solver, SolverOptions, SOLVERS};
+
+/// Formats the results of a benchmark can be written in.
+pub const FORMATS: [&str; 2] = ["csv", "json"];
+
+/// What to benchmark: every solver runs on `count` mazes for each size and generator.
+#[derive(Clone, Debug)]
+pub struct BenchConfig {
+    /// Sizes of the mazes, as (width, height).
+    pub sizes: Vec<(usize, usize)>,
+    pub generators: Vec<String>,
+    /// Number of mazes generated for each size and generator.
+    pub count: usize,
+    /// Seed of the first maze, the next ones use the following seeds.
+    pub seed: u64,
+    pub weighted: bool,
+    pub options: SolverOptions,
+}
+
+/// Measures of one solver run.
+#[derive(Clone, Debug)]
+pub struct BenchResult {
+    pub generator: String,
+    pub width: usize,
+    pub height: usize,
+    pub seed: u64,
+    pub solver: &'static str,
+    pub outcome: SolveOutcome,
+    pub steps: usize,
+    /// Number of distinct cells the solver went through or filled.
+    pub visited: usize,
+    /// Number of cells of the path found, or of the walk to the exit for solvers walking
+    /// through the maze without reporting a path, `None` if the solver found neither.
+    pub path_length: Option<usize>,
+    /// Number of cells of the shortest path from the start to the exit.
+    pub optimal_length: Option<usize>,
+    /// Total cost of the cells entered along the path or the walk, to compare with `optimal_cost`
+    /// on weighted mazes.
+    pub path_cost: Option<usize>,
+    /// Total cost of the cheapest path from the start to the exit.
+    pub optimal_cost: Option<usize>,
+    pub duration: Duration,
+}
+
+/// Runs every solver on every maze of `config`, `None` if a generator is unknown.
+pub fn run(config: &BenchConfig) -> Option<Vec<BenchResult>> {
+    let mut results = Vec::new();
+
+    for name in &config.generators {
+        for &(width, height) in &config.sizes {
+            for seed in (0..config.count as u64).map(|index| config.seed.wrapping_add(index)) {
+                let mut generator = generator::create(name)?;
+                let maze = if config.weighted {
+                    Maze::generate_weighted_with(generator.as_mut(), width, height, seed)
+                } else {
+                    Maze::generate_with(generator.as_mut(), width, height, seed)
+                };
+                let optimal_length = maze.shortest_path_length(maze.start, maze.exit);
+                let optimal_cost = maze.cheapest_path_cost(maze.start, maze.exit);
+
+                for solver_name in SOLVERS {
+                    let maze = Rc::new(RefCell::new(maze.clone()));
+                    let mut solver = solver::create(solver_name, maze.clone(), &config.options)?;
+                    let (outcome, steps, duration, walk) = measure(solver.as_mut());
+                    let path = solver.path();
+                    let (path_length, path_cost) = if !path.is_empty() {
+                        let maze = maze.borrow();
+                        (Some(path.len()), Some(path[1..].iter().map(|&cell| maze.cost(cell) as usize).sum()))
+                    } else if outcome == SolveOutcome::Reached && solver.direction().is_some() {
+                        (Some(walk.cells), Some(walk.cost))
+                    } else {
+                        (None, None)
+                    };
+
+                    results.push(BenchResult {
+                        generator: name.clone(),
+                        width,
+                        height,
+                        seed,
+                        solver: solver_name,
+                        outcome,
+                        steps,
+                        visited: maze.borrow().visited_count(),
+                        path_length,
+                        optimal_length,
+                        path_cost,
+                        optimal_cost,
+                        duration,
+                    });
+                }
+            }
+        }
+    }
+
+    Some(results)
+}
+
+/// Cells a solver went through, counted every time it moved to another cell.
+#[derive(Clone, Copy, Debug)]
+struct Walk {
+    /// Number of cells of the walk, the start included.
+    cells: usize,
+    /// Total cost of the cells entered after the start.
+    cost: usize,
+}
+
+/// Runs `solver` from the start within the default step budget, returning how it ended,
+/// the number of steps it took, how long it took and the walk of its current cell.
+///
+/// The walk is followed in a first run, so that the timed run only does the steps.
+fn measure(solver: &mut dyn Solver) -> (SolveOutcome, usize, Duration, Walk) {
+    let budget = step_budget(&solver.maze().borrow());
+
+    solver.reset();
+    let mut walk = Walk { cells: 1, cost: 0 };
+    let mut cell = solver.current_cell();
+    run_solver(solver, budget, |solver| {
+        let next = solver.current_cell();
+        if next != cell {
+            cell = next;
+            walk.cells += 1;
+            walk.cost += solver.maze().borrow().cost(cell) as usize;
+        }
+    });
+
+    solver.reset();
+    let started = Instant::now();
+    let (outcome, steps) = run_solver(solver, budget, |_| ());
+    (outcome, steps, started.elapsed(), walk)
+}
+
+/// Steps `solver` until it ends or takes `budget` steps, calling `after_step` after each step.
+/// Returns how it ended and the number of steps it took.
+fn run_solver(solver: &mut dyn Solver, budget: usize, mut after_step: impl FnMut(&dyn Solver)) -> (SolveOutcome, usize) {
+    let mut steps = 0;
+    let outcome = loop {
+        if let Some(outcome) = solver.outcome() {
+            break outcome;
+        }
+        if steps >= budget {
+            break SolveOutcome::BudgetExceeded;
+        }
+        solver.step();
+        steps += 1;
+        after_step(solver);
+    };
+    (outcome, steps)
+}
+
+/// Writes `results` as CSV, with a header line.
+pub fn write_csv(results: &[BenchResult], out: &mut impl Write) -> io::Result<()> {
+    let optional = |value: Option<usize>| value.map_or(String::new(), |value| value.to_string());
+
+    writeln!(
+        out,
+        "generator,width,height,seed,solver,outcome,steps,visited,path_length,optimal_length,path_cost,optimal_cost,time_us",
+    )?;
+    for result in results {
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            result.generator,
+            result.width,
+            result.height,
+            result.seed,
+            result.solver,
+            result.outcome.name(),
+            result.steps,
+            result.visited,
+            optional(result.path_length),
+            optional(result.optimal_length),
+            optional(result.path_cost),
+            optional(result.optimal_cost),
+            result.duration.as_micros(),
+        )?;
+    }
+    Ok(())
+}
+
+/// One result as written in JSON, with the duration in microseconds.
+#[cfg(feature = "json")]
+#[derive(serde::Serialize)]
+struct JsonResult<'a> {
+    generator: &'a str,
+    width: usize,
+    height: usize,
+    seed: u64,
+    solver: &'a str,
+    outcome: SolveOutcome,
+    steps: usize,
+    visited: usize,
+    path_length: Option<usize>,
+    optimal_length: Option<usize>,
+    path_cost: Option<usize>,
+    optimal_cost: Option<usize>,
+    time_us: u64,
+}
+
+/// Writes `results` as a JSON array of objects, using `null` for missing lengths and costs.
+#[cfg(feature = "json")]
+pub fn write_json(results: &[BenchResult], out: &mut impl Write) -> io::Result<()> {
+    let results: Vec<JsonResult> = results
+        .iter()
+        .map(|result| JsonResult {
+            generator: &result.generator,
+            width: result.width,
+            height: result.height,
+            seed: result.seed,
+            solver: result.solver,
+            outcome: result.outcome,
+            steps: result.steps,
+            visited: result.visited,
+            path_length: result.path_length,
+            optimal_length: result.optimal_length,
+            path_cost: result.path_cost,
+            optimal_cost: result.optimal_cost,
+            time_us: result.duration.as_micros() as u64,
+        })
+        .collect();
+
+    serde_json::to_writer_pretty(&mut *out, &results)?;
+    writeln!(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_config() -> BenchConfig {
+        BenchConfig {
+            sizes: vec![(6, 4), (5, 5)],
+            generators: vec!["backtracker".to_string(), "kruskal".to_string()],
+            count: 2,
+            seed: 42,
+            weighted: false,
+            options: SolverOptions::default(),
+        }
+    }
+
+    #[test]
+    fn test_bench_runs_every_solver() {
+        let results = run(&create_test_config()).unwrap();
+
+        assert_eq!(results.len(), 2 * 2 * 2 * SOLVERS.len());
+        assert!(SOLVERS.iter().all(|name| results.iter().any(|result| result.solver == *name)));
+        assert_eq!(results[0].seed, 42);
+        assert_eq!(results[SOLVERS.len()].seed, 43);
+    }
+
+    #[test]
+    fn test_bench_shortest_paths_are_optimal() {
+        let results = run(&create_test_config()).unwrap();
+
+        for result in results.iter().filter(|result| result.solver == "bfs") {
+            assert_eq!(result.outcome, SolveOutcome::Reached);
+            assert_eq!(result.path_length, result.optimal_length);
+            assert!(result.visited <= result.width * result.height);
+        }
+        for result in results.iter().filter(|result| result.solver == "right-hand" && result.outcome == SolveOutcome::Reached) {
+            let walked = result.path_length.expect("Wall followers should report the length of their walk");
+            assert!(walked >= result.optimal_length.unwrap());
+            assert_eq!(result.path_cost, Some(walked - 1), "Every cell costs 1 in a maze without weights");
+        }
+    }
+
+    #[test]
+    fn test_bench_weighted_costs() {
+        let mut config = create_test_config();
+        config.sizes = vec![(12, 12)];
+        config.weighted = true;
+        let results = run(&config).unwrap();
+
+        for result in &results {
+            if result.outcome == SolveOutcome::Reached {
+                assert!(result.path_cost >= result.optimal_cost, "{} can't beat the cheapest path", result.solver);
+            }
+            if result.solver == "astar" || result.solver == "dijkstra" {
+                assert_eq!(result.path_cost, result.optimal_cost, "{} should find the cheapest path", result.solver);
+            }
+        }
+        assert!(
+            results.iter().any(|result| result.solver == "bfs" && result.path_cost > result.optimal_cost),
+            "BFS should ignore the costs on some of the weighted mazes",
+        );
+    }
+
+    #[test]
+    fn test_bench_unknown_generator() {
+        let mut config = create_test_config();
+        config.generators.push("unknown".to_string());
+
+        assert!(run(&config).is_none());
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let mut config = create_test_config();
+        config.count = 1;
+        let results = run(&config).unwrap();
+        let mut out = Vec::new();
+
+        write_csv(&results, &mut out).unwrap();
+
+        let csv = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), results.len() + 1);
+        assert!(lines[0].starts_with("generator,width,height,seed,solver"));
+        assert!(lines[1].starts_with("backtracker,6,4,42,right-hand,reached,"));
+        assert!(lines.iter().all(|line| line.split(',').count() == 13));
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_write_json() {
+        let mut config = create_test_config();
+        config.count = 1;
+        config.sizes.truncate(1);
+        config.generators.truncate(1);
+        let mut results = run(&config).unwrap();
+        results[0].generator = "back\"tracker".to_string();
+        results[1].path_length = None;
+        let mut out = Vec::new();
+
+        write_json(&results, &mut out).unwrap();
+
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        let objects = json.as_array().unwrap();
+        assert_eq!(objects.len(), SOLVERS.len());
+        assert_eq!(objects[0]["generator"], "back\"tracker", "Strings should be escaped");
+        assert_eq!(objects[0]["width"], 6);
+        assert_eq!(objects[0]["outcome"], "reached");
+        assert!(objects[1]["path_length"].is_null());
+        assert!(objects.iter().all(|object| object["time_us"].is_u64()));
+    }
+}
@@ -0,0 +1,182 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use crate::maze::Maze;
+use crate::solver::{Parents, SolveOutcome, Solver};
+
+/// Breadth-first search, expanding one cell of the queue at each step.
+#[derive(Clone, Debug)]
+pub struct Bfs {
+    pub maze: Rc<RefCell<Maze>>,
+    queue: VecDeque<(usize, usize)>,
+    /// Cell each discovered cell was queued from, every discovered cell but the start has one.
+    parents: Parents,
+    finished: bool,
+}
+
+impl Bfs {
+    pub fn new(maze: Rc<RefCell<Maze>>) -> Self {
+        let (start, parents) = {
+            let maze = maze.borrow();
+            (maze.start, Parents::new(&maze))
+        };
+        Bfs {
+            maze,
+            queue: VecDeque::from([start]),
+            parents,
+            finished: false,
+        }
+    }
+}
+
+impl Solver for Bfs {
+    fn name(&self) -> &'static str {
+        "bfs"
+    }
+
+    fn maze(&self) -> &Rc<RefCell<Maze>> {
+        &self.maze
+    }
+
+    fn step(&mut self) {
+        if self.finished {
+            return;
+        }
+
+        let Some(cell) = self.queue.pop_front() else {
+            self.finished = true;
+            return;
+        };
+
+        let mut maze = self.maze.borrow_mut();
+        maze.current_cell = cell;
+        maze.set_visited(cell, true);
+
+        if cell == maze.exit {
+            self.finished = true;
+            return;
+        }
+
+        for neighbour in maze.accessible_neighbours(cell) {
+            if neighbour != maze.start && self.parents.get(neighbour).is_none() {
+                self.parents.insert(neighbour, cell);
+                self.queue.push_back(neighbour);
+            }
+        }
+    }
+
+    fn outcome(&self) -> Option<SolveOutcome> {
+        if !self.finished {
+            return None;
+        }
+
+        let maze = self.maze.borrow();
+        if maze.current_cell == maze.exit {
+            Some(SolveOutcome::Reached)
+        } else {
+            Some(SolveOutcome::Unreachable)
+        }
+    }
+
+    fn reset(&mut self) {
+        let mut maze = self.maze.borrow_mut();
+        let start = maze.start;
+        maze.reset_visited_cells();
+        maze.current_cell = start;
+
+        self.queue = VecDeque::from([start]);
+        self.parents.clear();
+        self.finished = false;
+    }
+
+    fn frontier(&self) -> Vec<(usize, usize)> {
+        self.queue.iter().copied().collect()
+    }
+
+    /// Queued cells are the ones discovered but not expanded yet.
+    fn in_frontier(&self, cell: (usize, usize)) -> bool {
+        let maze = self.maze.borrow();
+        (cell == maze.start || self.parents.get(cell).is_some()) && !maze.is_visited(cell)
+    }
+
+    fn path(&self) -> Vec<(usize, usize)> {
+        let maze = self.maze.borrow();
+        if !self.finished || maze.current_cell != maze.exit {
+            return Vec::new();
+        }
+
+        self.parents.path_to(maze.exit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_maze() -> Rc<RefCell<Maze>> {
+        let mut maze = Maze::new(3, 3);
+
+        // The exit is reached through the middle, the top border is a dead end
+        maze.open_adjacent_wall((0, 0), (0, 1));
+        maze.open_adjacent_wall((0, 1), (0, 2));
+        maze.open_adjacent_wall((0, 2), (1, 2));
+        maze.open_adjacent_wall((0, 1), (1, 1));
+        maze.open_adjacent_wall((1, 1), (2, 1));
+        maze.open_adjacent_wall((2, 1), (2, 2));
+        maze.open_adjacent_wall((1, 0), (2, 0));
+
+        Rc::new(RefCell::new(maze))
+    }
+
+    #[test]
+    fn test_bfs_step_expands_one_cell() {
+        let maze = create_test_maze();
+        let mut solver = Bfs::new(maze.clone());
+
+        solver.step();
+
+        assert_eq!(maze.borrow().current_cell, (0, 0));
+        assert!(maze.borrow().cell((0, 0)).visited, "The expanded cell should be settled");
+        assert_eq!(solver.frontier(), vec![(0, 1)], "Only the open neighbour should be queued");
+    }
+
+    #[test]
+    fn test_bfs_shortest_path() {
+        let maze = create_test_maze();
+        let mut solver = Bfs::new(maze.clone());
+
+        solver.automatic_execution();
+
+        assert!(solver.is_finished());
+        assert_eq!(maze.borrow().current_cell, (2, 2));
+        assert_eq!(solver.path(), vec![(0, 0), (0, 1), (1, 1), (2, 1), (2, 2)]);
+        assert!(!maze.borrow().cell((1, 0)).visited, "Unreachable cells should never be settled");
+    }
+
+    #[test]
+    fn test_bfs_unreachable_exit() {
+        let mut maze = Maze::new(3, 3);
+        maze.open_adjacent_wall((0, 0), (0, 1));
+        let maze = Rc::new(RefCell::new(maze));
+        let mut solver = Bfs::new(maze.clone());
+
+        solver.automatic_execution();
+
+        assert!(solver.is_finished(), "The search should stop once the queue is empty");
+        assert_eq!(solver.outcome(), Some(SolveOutcome::Unreachable));
+        assert!(solver.path().is_empty());
+    }
+
+    #[test]
+    fn test_bfs_reset() {
+        let maze = create_test_maze();
+        let mut solver = Bfs::new(maze.clone());
+
+        solver.automatic_execution();
+        solver.reset();
+
+        assert!(!solver.is_finished());
+        assert_eq!(solver.frontier(), vec![(0, 0)]);
+        assert!(!maze.borrow().cell((2, 2)).visited);
+    }
+}
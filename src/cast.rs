@@ -0,0 +1,200 @@
+//! Recording of the frames drawn by the TUI in the asciicast v2 format, which asciinema
+//! can play back: a JSON header line followed by one `[time, "o", data]` event per frame.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier, Style};
+
+/// Writes the frames drawn by the TUI to an asciicast file, timestamped with the time
+/// elapsed since the recording started.
+#[derive(Debug)]
+pub struct CastRecorder<W: Write> {
+    out: W,
+    started: Instant,
+    /// Last frame written, frames identical to it are skipped.
+    last: Option<Buffer>,
+}
+
+impl CastRecorder<BufWriter<File>> {
+    pub fn create(path: &str) -> io::Result<Self> {
+        Ok(CastRecorder::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> CastRecorder<W> {
+    /// Starts a recording, the header being written along with the first frame.
+    pub fn new(out: W) -> Self {
+        CastRecorder { out, started: Instant::now(), last: None }
+    }
+
+    /// Records `buffer` if it differs from the previous frame.
+    pub fn frame(&mut self, buffer: &Buffer) -> io::Result<()> {
+        let time = self.started.elapsed().as_micros() as f64 / 1e6;
+        match &self.last {
+            Some(last) if last == buffer => return Ok(()),
+            Some(last) if last.area != buffer.area => {
+                let size = format!("{}x{}", buffer.area.width, buffer.area.height);
+                writeln!(self.out, "{}", serde_json::to_string(&(time, "r", size))?)?;
+            }
+            Some(_) => (),
+            None => self.header(buffer)?,
+        }
+
+        writeln!(self.out, "{}", serde_json::to_string(&(time, "o", to_ansi(buffer)))?)?;
+        self.out.flush()?;
+        self.last = Some(buffer.clone());
+        Ok(())
+    }
+
+    fn header(&mut self, buffer: &Buffer) -> io::Result<()> {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+        let term = std::env::var("TERM").unwrap_or_else(|_| "xterm-256color".to_string());
+        let header = serde_json::json!({
+            "version": 2,
+            "width": buffer.area.width,
+            "height": buffer.area.height,
+            "timestamp": timestamp,
+            "title": "pathfinder",
+            "env": { "TERM": term },
+        });
+        writeln!(self.out, "{}", header)
+    }
+}
+
+/// Escape sequence parameter giving `color` to the foreground, or to the background
+/// when `background` is set.
+fn color_code(color: Color, background: bool) -> String {
+    let offset = if background { 10 } else { 0 };
+    let code = |code: u8| (code + offset).to_string();
+    match color {
+        Color::Reset => code(39),
+        Color::Black => code(30),
+        Color::Red => code(31),
+        Color::Green => code(32),
+        Color::Yellow => code(33),
+        Color::Blue => code(34),
+        Color::Magenta => code(35),
+        Color::Cyan => code(36),
+        Color::Gray => code(37),
+        Color::DarkGray => code(90),
+        Color::LightRed => code(91),
+        Color::LightGreen => code(92),
+        Color::LightYellow => code(93),
+        Color::LightBlue => code(94),
+        Color::LightMagenta => code(95),
+        Color::LightCyan => code(96),
+        Color::White => code(97),
+        Color::Rgb(red, green, blue) => format!("{};2;{};{};{}", 38 + offset, red, green, blue),
+        Color::Indexed(index) => format!("{};5;{}", 38 + offset, index),
+    }
+}
+
+/// Select Graphic Rendition sequence switching to `style`.
+fn sgr(style: Style) -> String {
+    let mut codes = vec!["0".to_string()];
+    for (modifier, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::CROSSED_OUT, "9"),
+    ] {
+        if style.add_modifier.contains(modifier) {
+            codes.push(code.to_string());
+        }
+    }
+    codes.push(color_code(style.fg.unwrap_or(Color::Reset), false));
+    codes.push(color_code(style.bg.unwrap_or(Color::Reset), true));
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+/// Draws the whole of `buffer` with escape sequences, one line at a time.
+fn to_ansi(buffer: &Buffer) -> String {
+    let mut ansi = String::new();
+    for y in 0..buffer.area.height {
+        ansi.push_str(&format!("\x1b[{};1H", y + 1));
+        let mut style = None;
+        for x in 0..buffer.area.width {
+            let cell = &buffer[(buffer.area.x + x, buffer.area.y + y)];
+            if cell.skip {
+                continue;
+            }
+            if style != Some(cell.style()) {
+                style = Some(cell.style());
+                ansi.push_str(&sgr(cell.style()));
+            }
+            ansi.push_str(cell.symbol());
+        }
+    }
+    ansi.push_str("\x1b[0m");
+    ansi
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::Stylize;
+
+    fn lines(out: Vec<u8>) -> Vec<serde_json::Value> {
+        String::from_utf8(out).unwrap().lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_to_ansi() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 4, 2));
+        buffer.set_string(1, 0, "P", Style::new().yellow());
+        buffer.set_string(0, 1, "E", Style::new().red().bold());
+
+        assert_eq!(
+            to_ansi(&buffer),
+            "\x1b[1;1H\x1b[0;39;49m \x1b[0;33;49mP\x1b[0;39;49m  \
+             \x1b[2;1H\x1b[0;1;31;49mE\x1b[0;39;49m   \x1b[0m",
+        );
+    }
+
+    #[test]
+    fn test_color_code() {
+        assert_eq!(color_code(Color::DarkGray, false), "90");
+        assert_eq!(color_code(Color::Blue, true), "44");
+        assert_eq!(color_code(Color::Rgb(139, 90, 43), false), "38;2;139;90;43");
+        assert_eq!(color_code(Color::Indexed(200), true), "48;5;200");
+    }
+
+    #[test]
+    fn test_recorder_writes_changed_frames() {
+        let mut out = Vec::new();
+        let mut recorder = CastRecorder::new(&mut out);
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 3, 1));
+
+        recorder.frame(&buffer).unwrap();
+        recorder.frame(&buffer).unwrap();
+        buffer.set_string(0, 0, "S", Style::new().green());
+        recorder.frame(&buffer).unwrap();
+
+        let lines = lines(out);
+        assert_eq!(lines.len(), 3, "The header and two distinct frames");
+        assert_eq!(lines[0]["version"], 2);
+        assert_eq!((lines[0]["width"].as_u64(), lines[0]["height"].as_u64()), (Some(3), Some(1)));
+        assert_eq!(lines[1][1], "o");
+        assert!(lines[2][2].as_str().unwrap().contains("\x1b[0;32;49mS"));
+        assert!(lines[1][0].as_f64().unwrap() <= lines[2][0].as_f64().unwrap());
+    }
+
+    #[test]
+    fn test_recorder_resize() {
+        let mut out = Vec::new();
+        let mut recorder = CastRecorder::new(&mut out);
+
+        recorder.frame(&Buffer::empty(Rect::new(0, 0, 3, 1))).unwrap();
+        recorder.frame(&Buffer::empty(Rect::new(0, 0, 5, 2))).unwrap();
+
+        let lines = lines(out);
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[2][1], "r");
+        assert_eq!(lines[2][2], "5x2");
+    }
+}
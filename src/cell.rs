@@ -1,16 +1,46 @@
-#[derive(Debug)]
+/// Traversal cost of a cell without terrain.
+pub const DEFAULT_COST: u32 = 1;
+/// Traversal cost of a cell covered with mud.
+pub const MUD_COST: u32 = 3;
+/// Traversal cost of a cell covered with water.
+pub const WATER_COST: u32 = 8;
+/// Highest traversal cost a maze can store, costs being kept in a byte per cell.
+pub const MAX_COST: u32 = u8::MAX as u32;
+
+/// Walls, flags and cost of one cell, as read from a [`Maze`](crate::maze::Maze) with
+/// `Maze::cell`. The maze stores each wall once, shared by the two cells on either side.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct Cell {
+    #[cfg_attr(feature = "json", serde(default, skip_serializing_if = "std::ops::Not::not"))]
     pub visited: bool,
+    /// Set by the filling solvers on the cells they ruled out of the solution.
+    #[cfg_attr(feature = "json", serde(default, skip_serializing_if = "std::ops::Not::not"))]
+    pub filled: bool,
+    /// Cost paid by weighted solvers to enter the cell, from 1 to `MAX_COST`.
+    pub cost: u32,
     pub top_wall : bool,
     pub bottom_wall : bool,
     pub left_wall : bool,
     pub right_wall : bool,
 }
 
+impl Cell {
+    /// Number of closed sides of the cell.
+    pub fn wall_count(&self) -> usize {
+        [self.top_wall, self.bottom_wall, self.left_wall, self.right_wall]
+            .iter()
+            .filter(|&&wall| wall)
+            .count()
+    }
+}
+
 impl Default for Cell {
     fn default() -> Self {
         Self {
             visited: false,
+            filled: false,
+            cost: DEFAULT_COST,
             top_wall: true,
             bottom_wall: true,
             left_wall: true,
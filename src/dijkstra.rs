@@ -0,0 +1,227 @@
+use std::cell::RefCell;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::rc::Rc;
+use crate::maze::{Maze, UNREACHED};
+use crate::solver::{Parents, SolveOutcome, Solver};
+
+/// Dijkstra's algorithm over the cell costs, settling one cell at each step.
+#[derive(Clone, Debug)]
+pub struct Dijkstra {
+    pub maze: Rc<RefCell<Maze>>,
+    queue: BinaryHeap<Reverse<(u32, (usize, usize))>>,
+    /// Whether each cell was settled, row by row.
+    settled: Vec<bool>,
+    /// Cost of the cheapest known path to each cell, row by row, `UNREACHED` until the cell is reached.
+    distances: Vec<u32>,
+    parents: Parents,
+    finished: bool,
+}
+
+impl Dijkstra {
+    pub fn new(maze: Rc<RefCell<Maze>>) -> Self {
+        let (start, cells, parents) = {
+            let maze = maze.borrow();
+            (maze.start, maze.width * maze.height, Parents::new(&maze))
+        };
+        let mut solver = Dijkstra {
+            maze,
+            queue: BinaryHeap::from([Reverse((0, start))]),
+            settled: vec![false; cells],
+            distances: vec![UNREACHED; cells],
+            parents,
+            finished: false,
+        };
+        let index = solver.index(start);
+        solver.distances[index] = 0;
+        solver
+    }
+
+    /// Total cost of the cheapest known path from the start to `cell`.
+    pub fn distance(&self, cell: (usize, usize)) -> Option<usize> {
+        Some(self.distances[self.index(cell)]).filter(|&distance| distance != UNREACHED).map(|distance| distance as usize)
+    }
+
+    fn index(&self, cell: (usize, usize)) -> usize {
+        self.maze.borrow().index(cell)
+    }
+}
+
+impl Solver for Dijkstra {
+    fn name(&self) -> &'static str {
+        "dijkstra"
+    }
+
+    fn maze(&self) -> &Rc<RefCell<Maze>> {
+        &self.maze
+    }
+
+    fn step(&mut self) {
+        if self.finished {
+            return;
+        }
+
+        let cell = loop {
+            match self.queue.pop() {
+                Some(Reverse((_, cell))) if self.settled[self.index(cell)] => continue,
+                Some(Reverse((_, cell))) => break cell,
+                None => {
+                    self.finished = true;
+                    return;
+                }
+            }
+        };
+
+        let index = self.index(cell);
+        let mut maze = self.maze.borrow_mut();
+        maze.current_cell = cell;
+        maze.set_visited(cell, true);
+        self.settled[index] = true;
+
+        if cell == maze.exit {
+            self.finished = true;
+            return;
+        }
+
+        let distance = self.distances[index];
+        for neighbour in maze.accessible_neighbours(cell) {
+            let index = maze.index(neighbour);
+            if self.settled[index] {
+                continue;
+            }
+
+            let candidate = maze.cost_through(distance, neighbour);
+            if self.distances[index] <= candidate {
+                continue;
+            }
+
+            self.distances[index] = candidate;
+            self.parents.insert(neighbour, cell);
+            self.queue.push(Reverse((candidate, neighbour)));
+        }
+    }
+
+    fn outcome(&self) -> Option<SolveOutcome> {
+        if !self.finished {
+            return None;
+        }
+
+        let maze = self.maze.borrow();
+        if maze.current_cell == maze.exit {
+            Some(SolveOutcome::Reached)
+        } else {
+            Some(SolveOutcome::Unreachable)
+        }
+    }
+
+    fn reset(&mut self) {
+        let start = self.maze.borrow().start;
+        {
+            let mut maze = self.maze.borrow_mut();
+            maze.reset_visited_cells();
+            maze.current_cell = start;
+        }
+
+        self.queue = BinaryHeap::from([Reverse((0, start))]);
+        self.settled.fill(false);
+        self.distances.fill(UNREACHED);
+        let index = self.index(start);
+        self.distances[index] = 0;
+        self.parents.clear();
+        self.finished = false;
+    }
+
+    fn frontier(&self) -> Vec<(usize, usize)> {
+        let mut cells: Vec<(usize, usize)> = self.queue
+            .iter()
+            .map(|&Reverse((_, cell))| cell)
+            .filter(|&cell| !self.settled[self.index(cell)])
+            .collect();
+        cells.sort_unstable();
+        cells.dedup();
+        cells
+    }
+
+    fn in_frontier(&self, cell: (usize, usize)) -> bool {
+        let index = self.index(cell);
+        self.distances[index] != UNREACHED && !self.settled[index]
+    }
+
+    fn scores(&self, cell: (usize, usize)) -> Option<(usize, f64)> {
+        self.distance(cell).map(|distance| (distance, distance as f64))
+    }
+
+    fn path(&self) -> Vec<(usize, usize)> {
+        let maze = self.maze.borrow();
+        if !self.finished || maze.current_cell != maze.exit {
+            return Vec::new();
+        }
+
+        self.parents.path_to(maze.exit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bfs::Bfs;
+    use crate::cell::WATER_COST;
+
+    fn create_test_maze() -> Rc<RefCell<Maze>> {
+        let mut maze = Maze::new(3, 3);
+
+        // Ring around the centre cell: both sides reach the exit in 4 moves
+        maze.open_adjacent_wall((0, 0), (0, 1));
+        maze.open_adjacent_wall((0, 1), (0, 2));
+        maze.open_adjacent_wall((0, 2), (1, 2));
+        maze.open_adjacent_wall((1, 2), (2, 2));
+        maze.open_adjacent_wall((0, 0), (1, 0));
+        maze.open_adjacent_wall((1, 0), (2, 0));
+        maze.open_adjacent_wall((2, 0), (2, 1));
+        maze.open_adjacent_wall((2, 1), (2, 2));
+
+        // Flooding the first cell of the left side makes the top route cheaper
+        maze.set_cost((1, 0), WATER_COST);
+
+        Rc::new(RefCell::new(maze))
+    }
+
+    #[test]
+    fn test_dijkstra_avoids_costly_cells() {
+        let maze = create_test_maze();
+        let mut solver = Dijkstra::new(maze.clone());
+
+        solver.automatic_execution();
+
+        assert_eq!(maze.borrow().current_cell, (2, 2));
+        assert_eq!(solver.path(), vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 2)]);
+        assert_eq!(solver.distance((2, 2)), Some(4));
+    }
+
+    #[test]
+    fn test_dijkstra_differs_from_bfs() {
+        let maze = create_test_maze();
+        let mut bfs = Bfs::new(maze.clone());
+        bfs.automatic_execution();
+        let bfs_path = bfs.path();
+
+        let mut dijkstra = Dijkstra::new(maze.clone());
+        dijkstra.automatic_execution();
+
+        assert!(bfs_path.contains(&(1, 0)), "BFS should ignore the cost and go through the water");
+        assert!(!dijkstra.path().contains(&(1, 0)), "Dijkstra should go around the water");
+    }
+
+    #[test]
+    fn test_dijkstra_reset() {
+        let maze = create_test_maze();
+        let mut solver = Dijkstra::new(maze.clone());
+
+        solver.automatic_execution();
+        solver.reset();
+
+        assert!(!solver.is_finished());
+        assert_eq!(solver.frontier(), vec![(0, 0)]);
+        assert_eq!(solver.distance((2, 2)), None);
+    }
+}
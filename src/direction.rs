@@ -0,0 +1,127 @@
+use crate::maze::Maze;
+
+/// Heading of a solver walking through the maze. North is towards row 0.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub fn turn_right(self) -> Direction {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    pub fn turn_left(self) -> Direction {
+        match self {
+            Direction::North => Direction::West,
+            Direction::East => Direction::North,
+            Direction::South => Direction::East,
+            Direction::West => Direction::South,
+        }
+    }
+
+    pub fn opposite(self) -> Direction {
+        self.turn_right().turn_right()
+    }
+
+    /// Direction of the move from `from` to the adjacent cell `to`.
+    pub fn between(from: (usize, usize), to: (usize, usize)) -> Option<Direction> {
+        if to.1 == from.1 && to.0 + 1 == from.0 {
+            Some(Direction::North)
+        } else if to.0 == from.0 && to.1 == from.1 + 1 {
+            Some(Direction::East)
+        } else if to.1 == from.1 && to.0 == from.0 + 1 {
+            Some(Direction::South)
+        } else if to.0 == from.0 && to.1 + 1 == from.1 {
+            Some(Direction::West)
+        } else {
+            None
+        }
+    }
+
+    /// Whether `maze` has a wall on this side of `cell`.
+    pub fn has_wall(self, maze: &Maze, cell: (usize, usize)) -> bool {
+        maze.has_wall(cell, self)
+    }
+
+    /// Cell next to `cell` this way, `None` above row 0 or left of column 0. The other
+    /// borders are up to the caller.
+    pub fn offset(self, (row, column): (usize, usize)) -> Option<(usize, usize)> {
+        match self {
+            Direction::North => Some((row.checked_sub(1)?, column)),
+            Direction::East => Some((row, column + 1)),
+            Direction::South => Some((row + 1, column)),
+            Direction::West => Some((row, column.checked_sub(1)?)),
+        }
+    }
+
+    /// Cell reached by moving one cell this way from `cell`, `None` when a wall or the
+    /// border of the maze is in the way.
+    pub fn step_from(self, maze: &Maze, cell: (usize, usize)) -> Option<(usize, usize)> {
+        if self.has_wall(maze, cell) {
+            return None;
+        }
+        self.offset(cell).filter(|&next| maze.contains(next))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turns() {
+        for direction in [Direction::North, Direction::East, Direction::South, Direction::West] {
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(direction.turn_right().turn_right(), direction.opposite());
+            assert_eq!(direction.turn_left().turn_left(), direction.opposite());
+            assert_ne!(direction.opposite(), direction);
+        }
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Direction::North.turn_left(), Direction::West);
+    }
+
+    #[test]
+    fn test_between() {
+        assert_eq!(Direction::between((1, 1), (0, 1)), Some(Direction::North));
+        assert_eq!(Direction::between((1, 1), (1, 2)), Some(Direction::East));
+        assert_eq!(Direction::between((1, 1), (2, 1)), Some(Direction::South));
+        assert_eq!(Direction::between((1, 1), (1, 0)), Some(Direction::West));
+        assert_eq!(Direction::between((1, 1), (2, 2)), None);
+        assert_eq!(Direction::between((1, 1), (1, 1)), None);
+    }
+
+    #[test]
+    fn test_offset() {
+        assert_eq!(Direction::North.offset((1, 1)), Some((0, 1)));
+        assert_eq!(Direction::East.offset((1, 1)), Some((1, 2)));
+        assert_eq!(Direction::North.offset((0, 1)), None);
+        assert_eq!(Direction::West.offset((1, 0)), None);
+        for direction in [Direction::North, Direction::East, Direction::South, Direction::West] {
+            assert_eq!(direction.offset((1, 1)).and_then(|cell| direction.opposite().offset(cell)), Some((1, 1)));
+        }
+    }
+
+    #[test]
+    fn test_step_from() {
+        let mut maze = Maze::new(2, 2);
+        maze.open_adjacent_wall((0, 0), (0, 1));
+
+        assert_eq!(Direction::East.step_from(&maze, (0, 0)), Some((0, 1)));
+        assert_eq!(Direction::West.step_from(&maze, (0, 1)), Some((0, 0)));
+        assert_eq!(Direction::South.step_from(&maze, (0, 0)), None, "Walls should block the way");
+
+        // A missing border wall still doesn't lead out of the maze
+        maze.set_wall((0, 0), Direction::North, false);
+        assert_eq!(Direction::North.step_from(&maze, (0, 0)), None);
+    }
+}
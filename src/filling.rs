@@ -0,0 +1,501 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use crate::maze::Maze;
+use crate::solver::{passage, Parents, SolveOutcome, Solver};
+
+/// Dead-end filling: fills the dead ends one cell at a time, then the cells they lead
+/// to once those became dead ends themselves, until only the corridors joining the
+/// start and the exit remain. Loops are never filled.
+#[derive(Clone, Debug)]
+pub struct DeadEndFilling {
+    pub maze: Rc<RefCell<Maze>>,
+    /// Dead ends waiting to be filled.
+    queue: VecDeque<(usize, usize)>,
+    path: Vec<(usize, usize)>,
+    finished: bool,
+}
+
+impl DeadEndFilling {
+    pub fn new(maze: Rc<RefCell<Maze>>) -> Self {
+        let queue = dead_ends(&maze.borrow());
+        DeadEndFilling {
+            maze,
+            queue,
+            path: Vec::new(),
+            finished: false,
+        }
+    }
+}
+
+/// Every dead end of the maze besides the start and the exit.
+fn dead_ends(maze: &Maze) -> VecDeque<(usize, usize)> {
+    (0..maze.height)
+        .flat_map(|i| (0..maze.width).map(move |j| (i, j)))
+        .filter(|&cell| is_dead_end(maze, cell))
+        .collect()
+}
+
+/// Whether `cell` can be filled: it is neither the start nor the exit, and at most one
+/// of its passages leads to a cell that isn't filled.
+fn is_dead_end(maze: &Maze, cell: (usize, usize)) -> bool {
+    cell != maze.start
+        && cell != maze.exit
+        && !maze.is_filled(cell)
+        && maze.accessible_neighbours(cell)
+            .filter(|&neighbour| !maze.is_filled(neighbour))
+            .count() <= 1
+}
+
+/// Shortest path from the start to the exit through the cells that aren't filled,
+/// empty if there is none.
+fn unfilled_path(maze: &Maze) -> Vec<(usize, usize)> {
+    let mut parents = Parents::new(maze);
+    let mut queue = VecDeque::from([maze.start]);
+    while let Some(cell) = queue.pop_front() {
+        if cell == maze.exit {
+            break;
+        }
+        for neighbour in maze.accessible_neighbours(cell) {
+            if !maze.is_filled(neighbour) && neighbour != maze.start && parents.get(neighbour).is_none() {
+                parents.insert(neighbour, cell);
+                queue.push_back(neighbour);
+            }
+        }
+    }
+
+    let path = parents.path_to(maze.exit);
+    if path.first() != Some(&maze.start) {
+        return Vec::new();
+    }
+    path
+}
+
+/// Ends a filling run: looks for the path left unfilled and moves to the exit if there is one.
+fn finish(maze: &mut Maze) -> Vec<(usize, usize)> {
+    let path = unfilled_path(maze);
+    if !path.is_empty() {
+        maze.current_cell = maze.exit;
+    }
+    path
+}
+
+/// Outcome shared by the filling solvers.
+fn filling_outcome(finished: bool, path: &[(usize, usize)]) -> Option<SolveOutcome> {
+    match (finished, path.is_empty()) {
+        (false, _) => None,
+        (true, false) => Some(SolveOutcome::Reached),
+        (true, true) => Some(SolveOutcome::Unreachable),
+    }
+}
+
+/// Puts `maze` back in its initial state before a filling run.
+fn reset_maze(maze: &mut Maze) {
+    maze.reset_visited_cells();
+    maze.current_cell = maze.start;
+}
+
+impl Solver for DeadEndFilling {
+    fn name(&self) -> &'static str {
+        "dead-end-filling"
+    }
+
+    fn maze(&self) -> &Rc<RefCell<Maze>> {
+        &self.maze
+    }
+
+    fn step(&mut self) {
+        if self.finished {
+            return;
+        }
+
+        let mut maze = self.maze.borrow_mut();
+        let cell = loop {
+            match self.queue.pop_front() {
+                Some(cell) if maze.is_filled(cell) => continue,
+                Some(cell) => break cell,
+                None => {
+                    self.path = finish(&mut maze);
+                    self.finished = true;
+                    return;
+                }
+            }
+        };
+
+        maze.set_filled(cell, true);
+        maze.current_cell = cell;
+        for neighbour in maze.accessible_neighbours(cell) {
+            if is_dead_end(&maze, neighbour) {
+                self.queue.push_back(neighbour);
+            }
+        }
+    }
+
+    fn outcome(&self) -> Option<SolveOutcome> {
+        filling_outcome(self.finished, &self.path)
+    }
+
+    fn reset(&mut self) {
+        let mut maze = self.maze.borrow_mut();
+        reset_maze(&mut maze);
+        self.queue = dead_ends(&maze);
+        self.path.clear();
+        self.finished = false;
+    }
+
+    fn frontier(&self) -> Vec<(usize, usize)> {
+        self.queue.iter().copied().collect()
+    }
+
+    /// Every dead end that isn't filled yet is queued: a cell is queued as soon as it becomes one.
+    fn in_frontier(&self, cell: (usize, usize)) -> bool {
+        is_dead_end(&self.maze.borrow(), cell)
+    }
+
+    fn path(&self) -> Vec<(usize, usize)> {
+        self.path.clone()
+    }
+}
+
+/// Cul-de-sac filling: also fills the parts of the maze, loops included, that are
+/// joined to the rest by a single passage and hold neither the start nor the exit.
+///
+/// Those single passages are the bridges of the maze. The cells behind them are
+/// filled one at a time, the farthest ones first.
+#[derive(Clone, Debug)]
+pub struct CulDeSacFilling {
+    pub maze: Rc<RefCell<Maze>>,
+    /// Indices of the cells left to fill, the next one last.
+    cells: Vec<u32>,
+    path: Vec<(usize, usize)>,
+    finished: bool,
+}
+
+impl CulDeSacFilling {
+    pub fn new(maze: Rc<RefCell<Maze>>) -> Self {
+        let cells = cul_de_sacs(&maze.borrow());
+        CulDeSacFilling {
+            maze,
+            cells,
+            path: Vec::new(),
+            finished: false,
+        }
+    }
+}
+
+/// Passages between adjacent cells, stored as two flags per cell for the passages going
+/// east and south of it.
+struct Passages {
+    width: usize,
+    flags: Vec<u8>,
+}
+
+impl Passages {
+    fn new(maze: &Maze) -> Self {
+        Passages { width: maze.width, flags: vec![0; maze.width * maze.height] }
+    }
+
+    /// Index in `flags` and bit of the passage between two adjacent cells.
+    fn slot(&self, first: (usize, usize), second: (usize, usize)) -> (usize, u8) {
+        let (from, to) = passage(first, second);
+        (from.0 * self.width + from.1, if from.0 == to.0 { 1 } else { 2 })
+    }
+
+    fn insert(&mut self, first: (usize, usize), second: (usize, usize)) {
+        let (index, bit) = self.slot(first, second);
+        self.flags[index] |= bit;
+    }
+
+    fn contains(&self, first: (usize, usize), second: (usize, usize)) -> bool {
+        let (index, bit) = self.slot(first, second);
+        self.flags[index] & bit != 0
+    }
+}
+
+/// Passages whose removal would split the maze in two, found with Tarjan's algorithm.
+fn bridges(maze: &Maze) -> Passages {
+    let mut order = vec![u32::MAX; maze.width * maze.height];
+    let mut low = vec![0; maze.width * maze.height];
+    let mut counter: u32 = 0;
+    let mut bridges = Passages::new(maze);
+
+    for root in (0..maze.height).flat_map(|row| (0..maze.width).map(move |col| (row, col))) {
+        if order[maze.index(root)] != u32::MAX {
+            continue;
+        }
+
+        // Depth-first search with an explicit stack of (cell, parent, next neighbour)
+        order[maze.index(root)] = counter;
+        low[maze.index(root)] = counter;
+        counter += 1;
+        let mut stack = vec![(root, None, 0)];
+
+        while let Some((cell, parent, next)) = stack.last_mut() {
+            let (cell, parent) = (*cell, *parent);
+            if let Some(neighbour) = maze.accessible_neighbours(cell).nth(*next) {
+                *next += 1;
+                if Some(neighbour) == parent {
+                    continue;
+                }
+                if order[maze.index(neighbour)] == u32::MAX {
+                    order[maze.index(neighbour)] = counter;
+                    low[maze.index(neighbour)] = counter;
+                    counter += 1;
+                    stack.push((neighbour, Some(cell), 0));
+                } else {
+                    low[maze.index(cell)] = low[maze.index(cell)].min(order[maze.index(neighbour)]);
+                }
+            } else {
+                stack.pop();
+                if let Some(parent) = parent {
+                    low[maze.index(parent)] = low[maze.index(parent)].min(low[maze.index(cell)]);
+                    if low[maze.index(cell)] > order[maze.index(parent)] {
+                        bridges.insert(parent, cell);
+                    }
+                }
+            }
+        }
+    }
+
+    bridges
+}
+
+/// Cells outside of the corridors and loops joining the start and the exit, in the
+/// order they should be filled, the next one last.
+fn cul_de_sacs(maze: &Maze) -> Vec<u32> {
+    let bridges = bridges(maze);
+
+    // Groups of cells still joined once the bridges are removed
+    let mut components = vec![u32::MAX; maze.width * maze.height];
+    let mut count: u32 = 0;
+    for root in (0..maze.height).flat_map(|row| (0..maze.width).map(move |col| (row, col))) {
+        if components[maze.index(root)] != u32::MAX {
+            continue;
+        }
+        components[maze.index(root)] = count;
+        let mut stack = vec![root];
+        while let Some(cell) = stack.pop() {
+            for neighbour in maze.accessible_neighbours(cell) {
+                if !bridges.contains(cell, neighbour) && components[maze.index(neighbour)] == u32::MAX {
+                    components[maze.index(neighbour)] = count;
+                    stack.push(neighbour);
+                }
+            }
+        }
+        count += 1;
+    }
+
+    // Groups on the way from the start to the exit: a shortest path never goes
+    // through a bridge twice, so it only crosses those groups
+    let mut kept = vec![false; count as usize];
+    kept[components[maze.index(maze.start)] as usize] = true;
+    for cell in unfilled_path(maze) {
+        kept[components[maze.index(cell)] as usize] = true;
+    }
+
+    // Distance of every cell to the kept part, so that filling starts from the far ends
+    let mut distances = vec![u32::MAX; maze.width * maze.height];
+    let mut queue = VecDeque::new();
+    for (index, &component) in components.iter().enumerate() {
+        if kept[component as usize] {
+            distances[index] = 0;
+            queue.push_back(maze.position(index));
+        }
+    }
+    while let Some(cell) = queue.pop_front() {
+        let distance = distances[maze.index(cell)];
+        for neighbour in maze.accessible_neighbours(cell) {
+            if distances[maze.index(neighbour)] == u32::MAX {
+                distances[maze.index(neighbour)] = distance + 1;
+                queue.push_back(neighbour);
+            }
+        }
+    }
+
+    let exit = maze.index(maze.exit);
+    let mut cells: Vec<u32> = (0..components.len())
+        .filter(|&index| !kept[components[index] as usize] && index != exit)
+        .map(|index| index as u32)
+        .collect();
+    cells.sort_by_key(|&index| (distances[index as usize], index));
+    cells
+}
+
+impl Solver for CulDeSacFilling {
+    fn name(&self) -> &'static str {
+        "cul-de-sac-filling"
+    }
+
+    fn maze(&self) -> &Rc<RefCell<Maze>> {
+        &self.maze
+    }
+
+    fn step(&mut self) {
+        if self.finished {
+            return;
+        }
+
+        let mut maze = self.maze.borrow_mut();
+        let Some(index) = self.cells.pop() else {
+            self.path = finish(&mut maze);
+            self.finished = true;
+            return;
+        };
+        let cell = maze.position(index as usize);
+
+        maze.set_filled(cell, true);
+        maze.current_cell = cell;
+    }
+
+    fn outcome(&self) -> Option<SolveOutcome> {
+        filling_outcome(self.finished, &self.path)
+    }
+
+    fn reset(&mut self) {
+        let mut maze = self.maze.borrow_mut();
+        reset_maze(&mut maze);
+        self.cells = cul_de_sacs(&maze);
+        self.path.clear();
+        self.finished = false;
+    }
+
+    fn path(&self) -> Vec<(usize, usize)> {
+        self.path.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bfs::Bfs;
+    use crate::generator::backtracker::Backtracker;
+    use crate::direction::Direction;
+
+    /// 4x3 maze with a loop around the pillar at (0,2) hanging off the corridor
+    /// going along the bottom row, and a dead end at (1,0).
+    ///
+    /// The start is at (2,0) and the exit at (2,3).
+    fn create_test_maze() -> Rc<RefCell<Maze>> {
+        let mut maze = Maze::new(4, 3);
+        maze.open_adjacent_wall((2, 0), (2, 1));
+        maze.open_adjacent_wall((2, 1), (2, 2));
+        maze.open_adjacent_wall((2, 2), (2, 3));
+        maze.open_adjacent_wall((2, 0), (1, 0));
+        maze.open_adjacent_wall((2, 1), (1, 1));
+        maze.open_adjacent_wall((1, 1), (0, 1));
+        maze.open_adjacent_wall((0, 1), (0, 0));
+        maze.open_adjacent_wall((1, 1), (1, 2));
+        maze.open_adjacent_wall((1, 2), (1, 3));
+        maze.open_adjacent_wall((1, 3), (0, 3));
+        maze.open_adjacent_wall((0, 3), (0, 2));
+        maze.open_adjacent_wall((0, 2), (0, 1));
+        maze.start = (2, 0);
+        maze.current_cell = (2, 0);
+
+        Rc::new(RefCell::new(maze))
+    }
+
+    fn filled_cells(maze: &Maze) -> Vec<(usize, usize)> {
+        let mut filled = Vec::new();
+        for ((i, j), cell) in maze.cells() {
+            if cell.filled {
+                filled.push((i, j));
+            }
+        }
+        filled
+    }
+
+    #[test]
+    fn test_dead_end_filling_perfect_maze() {
+        let maze = Rc::new(RefCell::new(Maze::generate_with(&mut Backtracker::default(), 8, 8, 42)));
+        let mut bfs = Bfs::new(maze.clone());
+        bfs.automatic_execution();
+        let shortest = bfs.path();
+
+        let mut solver = DeadEndFilling::new(maze.clone());
+        solver.reset();
+
+        assert_eq!(solver.automatic_execution(), SolveOutcome::Reached);
+        assert_eq!(solver.path(), shortest);
+        assert_eq!(filled_cells(&maze.borrow()).len(), 64 - shortest.len(), "Only the solution should be left");
+    }
+
+    #[test]
+    fn test_dead_end_filling_keeps_loops() {
+        let maze = create_test_maze();
+        let mut solver = DeadEndFilling::new(maze.clone());
+
+        assert_eq!(solver.automatic_execution(), SolveOutcome::Reached);
+        assert_eq!(filled_cells(&maze.borrow()), vec![(0, 0), (1, 0)]);
+        assert_eq!(solver.path(), vec![(2, 0), (2, 1), (2, 2), (2, 3)]);
+        assert_eq!(maze.borrow().current_cell, (2, 3));
+    }
+
+    #[test]
+    fn test_dead_end_filling_with_open_border() {
+        // The dead end at (0,0) has only two walls, the border above it being open
+        let maze = create_test_maze();
+        maze.borrow_mut().set_wall((0, 0), Direction::North, false);
+        let mut solver = DeadEndFilling::new(maze.clone());
+
+        assert_eq!(solver.automatic_execution(), SolveOutcome::Reached);
+        assert_eq!(filled_cells(&maze.borrow()), vec![(0, 0), (1, 0)]);
+    }
+
+    #[test]
+    fn test_bridges() {
+        let maze = create_test_maze();
+        let bridges = bridges(&maze.borrow());
+
+        assert!(bridges.contains((2, 1), (1, 1)));
+        assert!(bridges.contains((0, 1), (0, 0)));
+        assert!(!bridges.contains((1, 1), (1, 2)), "Passages on a loop aren't bridges");
+        let count: u32 = bridges.flags.iter().map(|flags| flags.count_ones()).sum();
+        assert_eq!(count, 6);
+    }
+
+    #[test]
+    fn test_cul_de_sac_filling_fills_loops() {
+        let maze = create_test_maze();
+        let mut solver = CulDeSacFilling::new(maze.clone());
+
+        solver.step();
+        assert_eq!(filled_cells(&maze.borrow()), vec![(0, 3)], "The farthest cell should be filled first");
+
+        assert_eq!(solver.automatic_execution(), SolveOutcome::Reached);
+        assert_eq!(
+            filled_cells(&maze.borrow()),
+            vec![(0, 0), (0, 1), (0, 2), (0, 3), (1, 0), (1, 1), (1, 2), (1, 3)],
+        );
+        assert_eq!(solver.path(), vec![(2, 0), (2, 1), (2, 2), (2, 3)]);
+    }
+
+    #[test]
+    fn test_filling_unreachable_exit() {
+        let maze = create_test_maze();
+        maze.borrow_mut().close_adjacent_wall((2, 2), (2, 3));
+
+        let mut dead_end = DeadEndFilling::new(maze.clone());
+        assert_eq!(dead_end.automatic_execution(), SolveOutcome::Unreachable);
+        assert!(dead_end.path().is_empty());
+
+        let mut cul_de_sac = CulDeSacFilling::new(maze.clone());
+        cul_de_sac.reset();
+        assert_eq!(cul_de_sac.automatic_execution(), SolveOutcome::Unreachable);
+        assert!(!maze.borrow().cell((2, 3)).filled, "The exit should never be filled");
+    }
+
+    #[test]
+    fn test_filling_reset() {
+        let maze = create_test_maze();
+        let mut solver = DeadEndFilling::new(maze.clone());
+
+        solver.automatic_execution();
+        solver.reset();
+
+        assert!(filled_cells(&maze.borrow()).is_empty());
+        assert_eq!(maze.borrow().current_cell, (2, 0));
+        assert_eq!(solver.frontier(), vec![(0, 0), (1, 0)]);
+        assert!(!solver.is_finished());
+    }
+}
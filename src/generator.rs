@@ -0,0 +1,265 @@
+use std::fmt::Debug;
+use rand::{Rng, SeedableRng};
+use crate::maze::{Maze, MazeRng};
+use crate::generator::aldous_broder::AldousBroder;
+use crate::generator::backtracker::Backtracker;
+use crate::generator::binary_tree::BinaryTree;
+use crate::generator::eller::Eller;
+use crate::generator::hunt_and_kill::HuntAndKill;
+use crate::generator::kruskal::Kruskal;
+use crate::generator::prim::Prim;
+use crate::generator::recursive_division::RecursiveDivision;
+use crate::generator::sidewinder::Sidewinder;
+use crate::generator::wilson::Wilson;
+
+pub mod aldous_broder;
+pub mod backtracker;
+pub mod binary_tree;
+pub mod eller;
+pub mod hunt_and_kill;
+pub mod kruskal;
+pub mod prim;
+pub mod recursive_division;
+pub mod sidewinder;
+pub mod wilson;
+
+/// Names of every generator that can be picked from the CLI.
+pub const GENERATORS: [&str; 10] = [
+    "backtracker",
+    "prim",
+    "kruskal",
+    "wilson",
+    "aldous-broder",
+    "hunt-and-kill",
+    "eller",
+    "binary-tree",
+    "sidewinder",
+    "recursive-division",
+];
+
+/// A maze generation algorithm, building the maze one piece at a time.
+///
+/// Generators start from a maze whose walls are all closed and may use
+/// `Cell::visited` as scratch space, the caller resets it once the maze is complete.
+pub trait Generator: Debug {
+    fn name(&self) -> &'static str;
+
+    /// Carves or builds the next piece of the maze.
+    /// Returns `false` once the maze was already complete.
+    fn step(&mut self, maze: &mut Maze, rng: &mut MazeRng) -> bool;
+
+    fn generate(&mut self, maze: &mut Maze, rng: &mut MazeRng) {
+        while self.step(maze, rng) {}
+    }
+
+    /// Cells the algorithm is currently working on, highlighted while the generation is animated.
+    fn active_cells(&self) -> Vec<(usize, usize)> {
+        Vec::new()
+    }
+
+    /// Whether `cell` is one of the active cells, looked up without listing them all.
+    fn is_active(&self, _cell: (usize, usize)) -> bool {
+        false
+    }
+
+    /// Set `cell` belongs to, for algorithms that track sets of connected cells.
+    fn set_of(&self, _cell: (usize, usize)) -> Option<usize> {
+        None
+    }
+}
+
+/// A generation in progress, stepped by the TUI to animate it.
+#[derive(Debug)]
+pub struct Generation {
+    generator: Box<dyn Generator>,
+    rng: MazeRng,
+    weighted: bool,
+    longest_path: bool,
+    finished: bool,
+}
+
+impl Generation {
+    /// Starts generating into `maze`, which must have all its walls closed.
+    /// Stepping until the end gives the same maze as `Maze::generate_with` or `Maze::generate_weighted_with`.
+    pub fn new(generator: Box<dyn Generator>, maze: &mut Maze, seed: u64, weighted: bool) -> Self {
+        maze.seed = Some(seed);
+        maze.generator = Some(generator.name());
+
+        Generation {
+            generator,
+            rng: MazeRng::seed_from_u64(seed),
+            weighted,
+            longest_path: false,
+            finished: false,
+        }
+    }
+
+    /// Moves the start and the exit to the ends of the longest path once the maze is complete.
+    pub fn with_longest_path(mut self) -> Self {
+        self.longest_path = true;
+        self
+    }
+
+    pub fn generator(&self) -> &dyn Generator {
+        self.generator.as_ref()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn step(&mut self, maze: &mut Maze) {
+        if self.finished {
+            return;
+        }
+
+        if !self.generator.step(maze, &mut self.rng) {
+            if self.weighted {
+                maze.add_weights(&mut self.rng);
+            }
+            if self.longest_path {
+                maze.place_on_longest_path();
+            }
+            self.finished = true;
+        }
+    }
+
+    pub fn finish(&mut self, maze: &mut Maze) {
+        while !self.finished {
+            self.step(maze);
+        }
+    }
+}
+
+pub fn create(name: &str) -> Option<Box<dyn Generator>> {
+    match name {
+        "backtracker" => Some(Box::new(Backtracker::default())),
+        "prim" => Some(Box::new(Prim::default())),
+        "kruskal" => Some(Box::new(Kruskal::default())),
+        "wilson" => Some(Box::new(Wilson::default())),
+        "aldous-broder" => Some(Box::new(AldousBroder::default())),
+        "hunt-and-kill" => Some(Box::new(HuntAndKill::default())),
+        "eller" => Some(Box::new(Eller::default())),
+        "binary-tree" => Some(Box::new(BinaryTree::default())),
+        "sidewinder" => Some(Box::new(Sidewinder::default())),
+        "recursive-division" => Some(Box::new(RecursiveDivision::default())),
+        _ => None,
+    }
+}
+
+fn random_cell(maze: &Maze, rng: &mut MazeRng) -> (usize, usize) {
+    (rng.random_range(0..maze.height), rng.random_range(0..maze.width))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// Checks that the maze is a spanning tree: every cell is reachable and there is no loop.
+    fn assert_perfect(maze: &Maze, name: &str) {
+        let mut passages = 0;
+        for ((i, j), cell) in maze.cells() {
+            if j + 1 < maze.width {
+                assert_eq!(cell.right_wall, maze.cell((i, j + 1)).left_wall, "{}: walls of ({},{}) disagree", name, i, j);
+                passages += usize::from(!cell.right_wall);
+            } else {
+                assert!(cell.right_wall, "{}: the border should stay closed", name);
+            }
+            if i + 1 < maze.height {
+                assert_eq!(cell.bottom_wall, maze.cell((i + 1, j)).top_wall, "{}: walls of ({},{}) disagree", name, i, j);
+                passages += usize::from(!cell.bottom_wall);
+            } else {
+                assert!(cell.bottom_wall, "{}: the border should stay closed", name);
+            }
+        }
+        assert_eq!(passages, maze.width * maze.height - 1, "{}: a perfect maze has one passage less than cells", name);
+
+        let mut reached = HashSet::from([(0, 0)]);
+        let mut stack = vec![(0, 0)];
+        while let Some((i, j)) = stack.pop() {
+            for neighbour in maze.get_accessible_neighbours(i, j) {
+                if reached.insert(neighbour) {
+                    stack.push(neighbour);
+                }
+            }
+        }
+        assert_eq!(reached.len(), maze.width * maze.height, "{}: every cell should be reachable", name);
+    }
+
+    #[test]
+    fn test_generators_build_perfect_mazes() {
+        for name in GENERATORS {
+            for (width, height) in [(1, 1), (1, 7), (7, 1), (12, 9)] {
+                let mut generator = create(name).expect("Every listed generator should be creatable");
+                assert_eq!(generator.name(), name);
+
+                let maze = Maze::generate_with(generator.as_mut(), width, height, 3);
+                assert_perfect(&maze, name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_generators_are_reproducible() {
+        for name in GENERATORS {
+            let first = Maze::generate_with(create(name).unwrap().as_mut(), 10, 10, 11);
+            let second = Maze::generate_with(create(name).unwrap().as_mut(), 10, 10, 11);
+
+            let walls = |maze: &Maze| -> Vec<(bool, bool)> {
+                maze.cells().map(|(_, cell)| (cell.right_wall, cell.bottom_wall)).collect()
+            };
+            assert_eq!(walls(&first), walls(&second), "{}: the same seed should give the same maze", name);
+        }
+    }
+
+    #[test]
+    fn test_generation_matches_direct_generation() {
+        for weighted in [false, true] {
+            let expected = if weighted {
+                Maze::generate_weighted_with(create("kruskal").unwrap().as_mut(), 8, 8, 21)
+            } else {
+                Maze::generate_with(create("kruskal").unwrap().as_mut(), 8, 8, 21)
+            };
+
+            let mut maze = Maze::new(8, 8);
+            let mut generation = Generation::new(create("kruskal").unwrap(), &mut maze, 21, weighted);
+            let mut steps = 0;
+            while !generation.is_finished() {
+                generation.step(&mut maze);
+                steps += 1;
+            }
+
+            let walls = |maze: &Maze| -> Vec<(bool, bool, u32)> {
+                maze.cells().map(|(_, cell)| (cell.right_wall, cell.bottom_wall, cell.cost)).collect()
+            };
+            assert!(steps > 1, "The generation should take several steps");
+            assert_eq!(walls(&maze), walls(&expected), "Animating should not change the generated maze");
+            assert_eq!(maze.seed, Some(21));
+        }
+    }
+
+    #[test]
+    fn test_is_active_matches_active_cells() {
+        for name in GENERATORS {
+            let mut maze = Maze::new(9, 7);
+            let mut rng = MazeRng::seed_from_u64(13);
+            let mut generator = create(name).unwrap();
+
+            loop {
+                let active: HashSet<(usize, usize)> = generator.active_cells().into_iter().collect();
+                for (cell, _) in maze.cells() {
+                    assert_eq!(generator.is_active(cell), active.contains(&cell), "{}: wrong state for {:?}", name, cell);
+                }
+                if !generator.step(&mut maze, &mut rng) {
+                    break;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_create_unknown_generator() {
+        assert!(create("unknown").is_none());
+    }
+}
@@ -0,0 +1,51 @@
+use rand::seq::IndexedRandom;
+use crate::generator::{random_cell, Generator};
+use crate::maze::{Maze, MazeRng};
+
+/// Random walk that carves a passage each time it enters a cell for the first time.
+#[derive(Debug, Default)]
+pub struct AldousBroder {
+    current: Option<(usize, usize)>,
+    remaining: usize,
+}
+
+impl Generator for AldousBroder {
+    fn name(&self) -> &'static str {
+        "aldous-broder"
+    }
+
+    fn step(&mut self, maze: &mut Maze, rng: &mut MazeRng) -> bool {
+        let Some(current) = self.current else {
+            let start = random_cell(maze, rng);
+            maze.set_visited(start, true);
+            maze.mark_carved(start);
+            self.current = Some(start);
+            self.remaining = maze.width * maze.height - 1;
+            return true;
+        };
+
+        if self.remaining == 0 {
+            return false;
+        }
+
+        let neighbours = maze.get_neighbours(current.0, current.1);
+        let next = *neighbours.choose(rng).unwrap();
+
+        if !maze.is_visited(next) {
+            maze.set_visited(next, true);
+            maze.open_adjacent_wall(current, next);
+            maze.mark_carved(next);
+            self.remaining -= 1;
+        }
+        self.current = Some(next);
+        true
+    }
+
+    fn active_cells(&self) -> Vec<(usize, usize)> {
+        self.current.into_iter().collect()
+    }
+
+    fn is_active(&self, cell: (usize, usize)) -> bool {
+        self.current == Some(cell)
+    }
+}
@@ -0,0 +1,105 @@
+use rand::Rng;
+use crate::direction::Direction;
+use crate::generator::Generator;
+use crate::maze::{Maze, MazeRng};
+
+/// Randomized depth-first search: walks to unvisited neighbours and backtracks on dead ends.
+///
+/// The walk is kept as the moves that led from (0, 0) to the current cell, a byte each,
+/// so that very large mazes don't need a stack of cell positions.
+#[derive(Debug, Default)]
+pub struct Backtracker {
+    moves: Vec<Direction>,
+    /// Cell at the end of the walk, `None` before the start and once the walk went back past (0, 0).
+    current: Option<(usize, usize)>,
+    /// Whether each cell is on the walk, row by row, to look cells up without following the moves.
+    on_walk: Vec<bool>,
+    width: usize,
+    started: bool,
+}
+
+impl Generator for Backtracker {
+    fn name(&self) -> &'static str {
+        "backtracker"
+    }
+
+    fn step(&mut self, maze: &mut Maze, rng: &mut MazeRng) -> bool {
+        if !self.started {
+            self.started = true;
+            self.width = maze.width;
+            self.on_walk = vec![false; maze.width * maze.height];
+            self.on_walk[0] = true;
+            maze.set_visited((0, 0), true);
+            self.current = Some((0, 0));
+            maze.mark_carved((0, 0));
+            return true;
+        }
+
+        let Some(current_cell) = self.current else {
+            return false;
+        };
+
+        let non_visited_neighbours = maze.get_non_visited_neighbours(current_cell.0, current_cell.1);
+
+        if non_visited_neighbours.is_empty() {
+            self.on_walk[maze.index(current_cell)] = false;
+            self.current = self.moves.pop().and_then(|direction| direction.opposite().offset(current_cell));
+        } else {
+            let random_number: usize = rng.random_range(0..non_visited_neighbours.len());
+            let selected_cell = non_visited_neighbours[random_number];
+
+            maze.set_visited(selected_cell, true);
+
+            maze.open_adjacent_wall(current_cell, selected_cell);
+
+            self.moves.extend(Direction::between(current_cell, selected_cell));
+            self.current = Some(selected_cell);
+            self.on_walk[maze.index(selected_cell)] = true;
+            maze.mark_carved(selected_cell);
+        }
+        true
+    }
+
+    fn active_cells(&self) -> Vec<(usize, usize)> {
+        if self.current.is_none() {
+            return Vec::new();
+        }
+
+        let mut cells = vec![(0, 0)];
+        let mut cell = (0, 0);
+        for direction in &self.moves {
+            cell = direction.offset(cell).expect("Moves should stay inside the maze");
+            cells.push(cell);
+        }
+        cells
+    }
+
+    fn is_active(&self, (row, col): (usize, usize)) -> bool {
+        self.on_walk.get(row * self.width + col).copied().unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use super::*;
+
+    #[test]
+    fn test_active_cells_follow_the_walk() {
+        let mut maze = Maze::new(4, 3);
+        let mut rng = MazeRng::seed_from_u64(5);
+        let mut generator = Backtracker::default();
+
+        for _ in 0..6 {
+            generator.step(&mut maze, &mut rng);
+            let cells = generator.active_cells();
+            assert_eq!(cells.first(), Some(&(0, 0)));
+            assert_eq!(cells.last().copied(), generator.current);
+            assert!(cells.windows(2).all(|pair| maze.get_accessible_neighbours(pair[0].0, pair[0].1).contains(&pair[1])));
+        }
+
+        generator.generate(&mut maze, &mut rng);
+        assert!(generator.active_cells().is_empty());
+        assert!(generator.moves.is_empty());
+    }
+}
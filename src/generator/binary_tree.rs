@@ -0,0 +1,69 @@
+use rand::seq::IndexedRandom;
+use crate::generator::Generator;
+use crate::maze::{Maze, MazeRng};
+
+/// Binary tree algorithm: every cell opens either its north or its west wall.
+#[derive(Debug, Default)]
+pub struct BinaryTree {
+    /// Index of the next cell to process, in row-major order.
+    next: usize,
+    width: usize,
+}
+
+impl Generator for BinaryTree {
+    fn name(&self) -> &'static str {
+        "binary-tree"
+    }
+
+    fn step(&mut self, maze: &mut Maze, rng: &mut MazeRng) -> bool {
+        if self.next >= maze.width * maze.height {
+            return false;
+        }
+
+        self.width = maze.width;
+        let cell = (self.next / maze.width, self.next % maze.width);
+        self.next += 1;
+
+        let mut candidates = Vec::new();
+        if cell.0 > 0 {
+            candidates.push((cell.0 - 1, cell.1));
+        }
+        if cell.1 > 0 {
+            candidates.push((cell.0, cell.1 - 1));
+        }
+
+        if let Some(&neighbour) = candidates.choose(rng) {
+            maze.open_adjacent_wall(cell, neighbour);
+        }
+        maze.mark_carved(cell);
+        true
+    }
+
+    fn active_cells(&self) -> Vec<(usize, usize)> {
+        match (self.next.checked_sub(1), self.width) {
+            (Some(last), width) if width > 0 => vec![(last / width, last % width)],
+            _ => Vec::new(),
+        }
+    }
+
+    fn is_active(&self, (row, col): (usize, usize)) -> bool {
+        self.next.checked_sub(1) == Some(row * self.width + col)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_binary_tree_opens_north_and_west_borders() {
+        let maze = Maze::generate_with(&mut BinaryTree::default(), 8, 6, 5);
+
+        for col in 1..maze.width {
+            assert!(!maze.cell((0, col)).left_wall, "The top row should be a single corridor");
+        }
+        for row in 1..maze.height {
+            assert!(!maze.cell((row, 0)).top_wall, "The left column should be a single corridor");
+        }
+    }
+}
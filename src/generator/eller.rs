@@ -0,0 +1,111 @@
+use std::collections::BTreeMap;
+use rand::seq::IndexedRandom;
+use rand::Rng;
+use crate::generator::Generator;
+use crate::maze::{Maze, MazeRng};
+
+/// Eller's algorithm: builds the maze row by row, only keeping the sets of the current row.
+#[derive(Debug, Default)]
+pub struct Eller {
+    row: usize,
+    /// Set of each cell of the current row, 0 for cells without a set yet.
+    sets: Vec<usize>,
+    next_set: usize,
+    /// Parent of each column in the union-find forest of the row being joined, the roots
+    /// holding the set in `sets`.
+    parents: Vec<usize>,
+}
+
+impl Eller {
+    /// Column holding the set of `col`, shortening the path to it on the way.
+    fn find(&mut self, mut col: usize) -> usize {
+        while self.parents[col] != col {
+            self.parents[col] = self.parents[self.parents[col]];
+            col = self.parents[col];
+        }
+        col
+    }
+}
+
+impl Generator for Eller {
+    fn name(&self) -> &'static str {
+        "eller"
+    }
+
+    fn step(&mut self, maze: &mut Maze, rng: &mut MazeRng) -> bool {
+        if self.row >= maze.height {
+            return false;
+        }
+
+        let row = self.row;
+        let last_row = row + 1 == maze.height;
+        if row == 0 {
+            self.sets = vec![0; maze.width];
+            self.next_set = 1;
+        }
+
+        for set in self.sets.iter_mut().filter(|set| **set == 0) {
+            *set = self.next_set;
+            self.next_set += 1;
+        }
+
+        // Cells that went down from the same set start out joined
+        let mut roots = BTreeMap::new();
+        self.parents = (0..maze.width).map(|col| *roots.entry(self.sets[col]).or_insert(col)).collect();
+
+        // Join adjacent cells of different sets, all of them on the last row
+        for col in 0..maze.width.saturating_sub(1) {
+            let (left, right) = (self.find(col), self.find(col + 1));
+            if left != right && (last_row || rng.random_bool(0.5)) {
+                maze.open_adjacent_wall((row, col), (row, col + 1));
+                self.parents[right] = left;
+            }
+        }
+        for col in 0..maze.width {
+            let root = self.find(col);
+            self.sets[col] = self.sets[root];
+        }
+
+        if !last_row {
+            let mut members: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+            for (col, &set) in self.sets.iter().enumerate() {
+                members.entry(set).or_default().push(col);
+            }
+
+            // Every set goes down at least once so that it stays connected to the rest of the maze
+            let mut next_sets = vec![0; maze.width];
+            for (set, cols) in members {
+                let mandatory = *cols.choose(rng).unwrap();
+                for col in cols {
+                    if col == mandatory || rng.random_bool(0.3) {
+                        maze.open_adjacent_wall((row, col), (row + 1, col));
+                        next_sets[col] = set;
+                    }
+                }
+            }
+            self.sets = next_sets;
+        }
+
+        for col in 0..maze.width {
+            maze.mark_carved((row, col));
+        }
+        self.row += 1;
+        true
+    }
+
+    /// The row that will be processed next.
+    fn active_cells(&self) -> Vec<(usize, usize)> {
+        (0..self.sets.len()).map(|col| (self.row, col)).collect()
+    }
+
+    fn is_active(&self, (row, col): (usize, usize)) -> bool {
+        row == self.row && col < self.sets.len()
+    }
+
+    fn set_of(&self, cell: (usize, usize)) -> Option<usize> {
+        if cell.0 != self.row {
+            return None;
+        }
+        self.sets.get(cell.1).copied().filter(|&set| set != 0)
+    }
+}
@@ -0,0 +1,85 @@
+use rand::seq::IndexedRandom;
+use crate::generator::Generator;
+use crate::maze::{Maze, MazeRng};
+
+/// Random walk to unvisited cells which, on dead ends, hunts for an unvisited cell next to the maze.
+#[derive(Debug, Default)]
+pub struct HuntAndKill {
+    current: Option<(usize, usize)>,
+    /// Rows before this one are entirely part of the maze.
+    hunt_row: usize,
+    started: bool,
+}
+
+impl HuntAndKill {
+    /// Finds an unvisited cell next to the maze and connects it, returns `None` once the maze is complete.
+    fn hunt(&mut self, maze: &mut Maze, rng: &mut MazeRng) -> Option<(usize, usize)> {
+        let mut first_incomplete_row = None;
+
+        for row in self.hunt_row..maze.height {
+            for col in 0..maze.width {
+                let cell = (row, col);
+                if maze.is_visited((row, col)) {
+                    continue;
+                }
+                first_incomplete_row.get_or_insert(row);
+
+                let in_maze: Vec<(usize, usize)> = maze.get_neighbours(row, col)
+                    .into_iter()
+                    .filter(|&(i, j)| maze.is_visited((i, j)))
+                    .collect();
+                if let Some(&connection) = in_maze.choose(rng) {
+                    maze.open_adjacent_wall(cell, connection);
+                    maze.set_visited((row, col), true);
+                    maze.mark_carved(cell);
+                    self.hunt_row = first_incomplete_row.unwrap();
+                    return Some(cell);
+                }
+            }
+        }
+
+        self.hunt_row = maze.height;
+        None
+    }
+}
+
+impl Generator for HuntAndKill {
+    fn name(&self) -> &'static str {
+        "hunt-and-kill"
+    }
+
+    fn step(&mut self, maze: &mut Maze, rng: &mut MazeRng) -> bool {
+        if !self.started {
+            self.started = true;
+            maze.set_visited((0, 0), true);
+            maze.mark_carved((0, 0));
+            self.current = Some((0, 0));
+            return true;
+        }
+
+        if let Some(current) = self.current {
+            let non_visited_neighbours = maze.get_non_visited_neighbours(current.0, current.1);
+            match non_visited_neighbours.choose(rng) {
+                Some(&next) => {
+                    maze.open_adjacent_wall(current, next);
+                    maze.set_visited(next, true);
+                    maze.mark_carved(next);
+                    self.current = Some(next);
+                },
+                None => self.current = None,
+            }
+            return true;
+        }
+
+        self.current = self.hunt(maze, rng);
+        self.current.is_some()
+    }
+
+    fn active_cells(&self) -> Vec<(usize, usize)> {
+        self.current.into_iter().collect()
+    }
+
+    fn is_active(&self, cell: (usize, usize)) -> bool {
+        self.current == Some(cell)
+    }
+}
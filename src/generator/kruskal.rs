@@ -0,0 +1,85 @@
+use rand::seq::SliceRandom;
+use crate::generator::Generator;
+use crate::maze::{Maze, MazeRng};
+
+/// Randomized Kruskal's algorithm: removes walls in random order when they separate two different sets.
+#[derive(Debug, Default)]
+pub struct Kruskal {
+    /// Walls left to try, each packed as the index of the cell west or north of it times
+    /// two, plus one for the wall below that cell.
+    edges: Vec<u64>,
+    /// Parent of each cell in the union-find forest, as a cell index.
+    parents: Vec<u32>,
+    width: usize,
+    started: bool,
+}
+
+impl Kruskal {
+    /// Representative of the set of connected cells containing `cell`.
+    fn root(&self, cell: (usize, usize)) -> usize {
+        let mut index = cell.0 * self.width + cell.1;
+        while self.parents[index] as usize != index {
+            index = self.parents[index] as usize;
+        }
+        index
+    }
+
+    fn find(&mut self, cell: (usize, usize)) -> usize {
+        let root = self.root(cell);
+
+        // Path compression, so that later lookups stay short
+        let mut index = cell.0 * self.width + cell.1;
+        while self.parents[index] as usize != root {
+            let next = self.parents[index] as usize;
+            self.parents[index] = root as u32;
+            index = next;
+        }
+        root
+    }
+}
+
+impl Generator for Kruskal {
+    fn name(&self) -> &'static str {
+        "kruskal"
+    }
+
+    fn step(&mut self, maze: &mut Maze, rng: &mut MazeRng) -> bool {
+        if !self.started {
+            self.started = true;
+            self.width = maze.width;
+            self.parents = (0..(maze.width * maze.height) as u32).collect();
+
+            for i in 0..maze.height {
+                for j in 0..maze.width {
+                    let edge = maze.index((i, j)) as u64 * 2;
+                    if j + 1 < maze.width {
+                        self.edges.push(edge);
+                    }
+                    if i + 1 < maze.height {
+                        self.edges.push(edge + 1);
+                    }
+                }
+            }
+            self.edges.shuffle(rng);
+            return true;
+        }
+
+        while let Some(edge) = self.edges.pop() {
+            let first = maze.position((edge / 2) as usize);
+            let second = if edge % 2 == 0 { (first.0, first.1 + 1) } else { (first.0 + 1, first.1) };
+
+            let (first_set, second_set) = (self.find(first), self.find(second));
+            if first_set != second_set {
+                self.parents[second_set] = first_set as u32;
+                maze.open_adjacent_wall(first, second);
+                maze.mark_carved(second);
+                return true;
+            }
+        }
+        false
+    }
+
+    fn set_of(&self, cell: (usize, usize)) -> Option<usize> {
+        (!self.parents.is_empty()).then(|| self.root(cell))
+    }
+}
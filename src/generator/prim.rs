@@ -0,0 +1,66 @@
+use std::collections::HashSet;
+use rand::seq::IndexedRandom;
+use rand::Rng;
+use crate::generator::{random_cell, Generator};
+use crate::maze::{Maze, MazeRng};
+
+/// Randomized Prim's algorithm: grows the maze from a random frontier cell at each step.
+#[derive(Debug, Default)]
+pub struct Prim {
+    frontier: Vec<(usize, usize)>,
+    in_frontier: HashSet<(usize, usize)>,
+    started: bool,
+}
+
+impl Prim {
+    fn add_cell(&mut self, maze: &mut Maze, cell: (usize, usize)) {
+        maze.set_visited(cell, true);
+        maze.mark_carved(cell);
+
+        for neighbour in maze.get_non_visited_neighbours(cell.0, cell.1) {
+            if self.in_frontier.insert(neighbour) {
+                self.frontier.push(neighbour);
+            }
+        }
+    }
+}
+
+impl Generator for Prim {
+    fn name(&self) -> &'static str {
+        "prim"
+    }
+
+    fn step(&mut self, maze: &mut Maze, rng: &mut MazeRng) -> bool {
+        if !self.started {
+            self.started = true;
+            let start = random_cell(maze, rng);
+            self.add_cell(maze, start);
+            return true;
+        }
+
+        if self.frontier.is_empty() {
+            return false;
+        }
+
+        let cell = self.frontier.swap_remove(rng.random_range(0..self.frontier.len()));
+        self.in_frontier.remove(&cell);
+
+        let in_maze: Vec<(usize, usize)> = maze.get_neighbours(cell.0, cell.1)
+            .into_iter()
+            .filter(|&(row, col)| maze.is_visited((row, col)))
+            .collect();
+        let connection = *in_maze.choose(rng).unwrap();
+
+        maze.open_adjacent_wall(cell, connection);
+        self.add_cell(maze, cell);
+        true
+    }
+
+    fn active_cells(&self) -> Vec<(usize, usize)> {
+        self.frontier.clone()
+    }
+
+    fn is_active(&self, cell: (usize, usize)) -> bool {
+        self.in_frontier.contains(&cell)
+    }
+}
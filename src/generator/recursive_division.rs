@@ -0,0 +1,139 @@
+use rand::Rng;
+use crate::generator::Generator;
+use crate::maze::{Maze, MazeRng};
+
+/// Rectangular area of the maze that still has to be divided.
+#[derive(Clone, Copy, Debug)]
+struct Chamber {
+    row: usize,
+    col: usize,
+    height: usize,
+    width: usize,
+}
+
+/// Recursive division: starts from an empty area and splits it with walls that have a single gap.
+///
+/// It builds walls rather than carving passages, so the cells along each new wall are the ones
+/// marked as carved, the gap last.
+#[derive(Debug, Default)]
+pub struct RecursiveDivision {
+    chambers: Vec<Chamber>,
+    started: bool,
+}
+
+impl RecursiveDivision {
+    fn open_interior(maze: &mut Maze) {
+        for i in 0..maze.height {
+            for j in 0..maze.width {
+                if j + 1 < maze.width {
+                    maze.open_adjacent_wall((i, j), (i, j + 1));
+                }
+                if i + 1 < maze.height {
+                    maze.open_adjacent_wall((i, j), (i + 1, j));
+                }
+            }
+        }
+    }
+}
+
+impl Generator for RecursiveDivision {
+    fn name(&self) -> &'static str {
+        "recursive-division"
+    }
+
+    fn step(&mut self, maze: &mut Maze, rng: &mut MazeRng) -> bool {
+        if !self.started {
+            self.started = true;
+            RecursiveDivision::open_interior(maze);
+            self.chambers.push(Chamber { row: 0, col: 0, height: maze.height, width: maze.width });
+            return true;
+        }
+
+        while let Some(chamber) = self.chambers.pop() {
+            if chamber.width < 2 || chamber.height < 2 {
+                continue;
+            }
+
+            let horizontal = match chamber.height.cmp(&chamber.width) {
+                std::cmp::Ordering::Greater => true,
+                std::cmp::Ordering::Less => false,
+                std::cmp::Ordering::Equal => rng.random_bool(0.5),
+            };
+
+            if horizontal {
+                // Wall between rows `row + above - 1` and `row + above`
+                let above = rng.random_range(1..chamber.height);
+                let gap = rng.random_range(0..chamber.width);
+                for j in (0..chamber.width).filter(|&j| j != gap) {
+                    let col = chamber.col + j;
+                    maze.close_adjacent_wall((chamber.row + above - 1, col), (chamber.row + above, col));
+                    maze.mark_carved((chamber.row + above - 1, col));
+                }
+                maze.mark_carved((chamber.row + above - 1, chamber.col + gap));
+
+                self.chambers.push(Chamber { height: above, ..chamber });
+                self.chambers.push(Chamber { row: chamber.row + above, height: chamber.height - above, ..chamber });
+            } else {
+                // Wall between columns `col + left - 1` and `col + left`
+                let left = rng.random_range(1..chamber.width);
+                let gap = rng.random_range(0..chamber.height);
+                for i in (0..chamber.height).filter(|&i| i != gap) {
+                    let row = chamber.row + i;
+                    maze.close_adjacent_wall((row, chamber.col + left - 1), (row, chamber.col + left));
+                    maze.mark_carved((row, chamber.col + left - 1));
+                }
+                maze.mark_carved((chamber.row + gap, chamber.col + left - 1));
+
+                self.chambers.push(Chamber { width: left, ..chamber });
+                self.chambers.push(Chamber { col: chamber.col + left, width: chamber.width - left, ..chamber });
+            }
+            return true;
+        }
+        false
+    }
+
+    /// The chamber that will be divided next.
+    fn active_cells(&self) -> Vec<(usize, usize)> {
+        let Some(chamber) = self.chambers.last() else {
+            return Vec::new();
+        };
+        (chamber.row..chamber.row + chamber.height)
+            .flat_map(|row| (chamber.col..chamber.col + chamber.width).map(move |col| (row, col)))
+            .collect()
+    }
+
+    fn is_active(&self, (row, col): (usize, usize)) -> bool {
+        self.chambers.last().is_some_and(|chamber| {
+            (chamber.row..chamber.row + chamber.height).contains(&row)
+                && (chamber.col..chamber.col + chamber.width).contains(&col)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use super::*;
+
+    #[test]
+    fn test_last_carved_is_the_gap_of_the_new_wall() {
+        let mut maze = Maze::new(6, 4);
+        let mut rng = MazeRng::seed_from_u64(3);
+        let mut generator = RecursiveDivision::default();
+
+        generator.step(&mut maze, &mut rng);
+        assert_eq!(maze.last_carved, None, "Opening the interior builds no wall");
+
+        let mut previous = None;
+        for _ in 0..4 {
+            let carved = maze.carved;
+            generator.step(&mut maze, &mut rng);
+
+            let gap = maze.last_carved.expect("Every division should mark the cells along its wall");
+            assert!(maze.carved > carved);
+            assert_ne!(maze.last_carved, previous, "The marker should move to the new wall");
+            assert!(!maze.get_accessible_neighbours(gap.0, gap.1).is_empty());
+            previous = maze.last_carved;
+        }
+    }
+}
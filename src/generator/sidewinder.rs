@@ -0,0 +1,74 @@
+use rand::Rng;
+use crate::generator::Generator;
+use crate::maze::{Maze, MazeRng};
+
+/// Sidewinder algorithm: builds horizontal runs and opens one of their cells to the north.
+#[derive(Debug, Default)]
+pub struct Sidewinder {
+    /// Index of the next cell to process, in row-major order.
+    next: usize,
+    run_start: usize,
+    width: usize,
+}
+
+impl Generator for Sidewinder {
+    fn name(&self) -> &'static str {
+        "sidewinder"
+    }
+
+    fn step(&mut self, maze: &mut Maze, rng: &mut MazeRng) -> bool {
+        if self.next >= maze.width * maze.height {
+            return false;
+        }
+
+        self.width = maze.width;
+        let (row, col) = (self.next / maze.width, self.next % maze.width);
+        self.next += 1;
+        if col == 0 {
+            self.run_start = 0;
+        }
+
+        let at_east_border = col + 1 == maze.width;
+        let close_run = row > 0 && (at_east_border || rng.random_bool(0.5));
+
+        if close_run {
+            let chosen = rng.random_range(self.run_start..=col);
+            maze.open_adjacent_wall((row, chosen), (row - 1, chosen));
+            self.run_start = col + 1;
+        } else if !at_east_border {
+            maze.open_adjacent_wall((row, col), (row, col + 1));
+        }
+        maze.mark_carved((row, col));
+        true
+    }
+
+    /// The cells of the current horizontal run.
+    fn active_cells(&self) -> Vec<(usize, usize)> {
+        let Some(last) = self.next.checked_sub(1) else {
+            return Vec::new();
+        };
+        let (row, col) = (last / self.width, last % self.width);
+        (self.run_start..=col).map(|col| (row, col)).collect()
+    }
+
+    fn is_active(&self, cell: (usize, usize)) -> bool {
+        let Some(last) = self.next.checked_sub(1) else {
+            return false;
+        };
+        cell.0 == last / self.width && (self.run_start..=last % self.width).contains(&cell.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sidewinder_opens_top_row() {
+        let maze = Maze::generate_with(&mut Sidewinder::default(), 8, 6, 5);
+
+        for col in 1..maze.width {
+            assert!(!maze.cell((0, col)).left_wall, "The top row should be a single corridor");
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use rand::seq::IndexedRandom;
+use crate::generator::{random_cell, Generator};
+use crate::maze::{Maze, MazeRng};
+
+/// Wilson's algorithm: loop-erased random walks from outside cells until they hit the maze.
+#[derive(Debug, Default)]
+pub struct Wilson {
+    walk: Vec<(usize, usize)>,
+    positions: HashMap<(usize, usize), usize>,
+    /// Index of the first cell that may still be outside of the maze, in row-major order.
+    next_start: usize,
+    started: bool,
+}
+
+impl Wilson {
+    /// Starts a new walk from the first cell outside of the maze, returns `false` if there is none.
+    fn start_walk(&mut self, maze: &Maze) -> bool {
+        while self.next_start < maze.width * maze.height {
+            let cell = (self.next_start / maze.width, self.next_start % maze.width);
+            if !maze.is_visited(cell) {
+                self.walk.push(cell);
+                self.positions.insert(cell, 0);
+                return true;
+            }
+            self.next_start += 1;
+        }
+        false
+    }
+
+    /// Adds the cells of the walk to the maze, ending with the connection to `target`.
+    fn carve_walk(&mut self, maze: &mut Maze, target: (usize, usize)) {
+        self.walk.push(target);
+        for pair in self.walk.windows(2) {
+            maze.open_adjacent_wall(pair[0], pair[1]);
+        }
+        for &cell in &self.walk[..self.walk.len() - 1] {
+            maze.set_visited(cell, true);
+            maze.mark_carved(cell);
+        }
+        self.walk.clear();
+        self.positions.clear();
+    }
+}
+
+impl Generator for Wilson {
+    fn name(&self) -> &'static str {
+        "wilson"
+    }
+
+    fn step(&mut self, maze: &mut Maze, rng: &mut MazeRng) -> bool {
+        if !self.started {
+            self.started = true;
+            let start = random_cell(maze, rng);
+            maze.set_visited(start, true);
+            maze.mark_carved(start);
+            return true;
+        }
+
+        let Some(&current) = self.walk.last() else {
+            return self.start_walk(maze);
+        };
+
+        let neighbours = maze.get_neighbours(current.0, current.1);
+        let next = *neighbours.choose(rng).unwrap();
+
+        if maze.is_visited(next) {
+            self.carve_walk(maze, next);
+        } else if let Some(&position) = self.positions.get(&next) {
+            // Erase the loop the walk just made
+            for cell in self.walk.drain(position + 1..) {
+                self.positions.remove(&cell);
+            }
+        } else {
+            self.positions.insert(next, self.walk.len());
+            self.walk.push(next);
+        }
+        true
+    }
+
+    fn active_cells(&self) -> Vec<(usize, usize)> {
+        self.walk.clone()
+    }
+
+    fn is_active(&self, cell: (usize, usize)) -> bool {
+        self.positions.contains_key(&cell)
+    }
+}
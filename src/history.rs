@@ -0,0 +1,376 @@
+use std::collections::BTreeMap;
+use crate::direction::Direction;
+use crate::maze::Maze;
+use crate::solver::Solver;
+
+/// What a single solver step did.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct StepRecord {
+    pub position: (usize, usize),
+    pub direction: Option<Direction>,
+    /// Cell reached for the first time during the step, if any.
+    pub visited: Option<(usize, usize)>,
+}
+
+/// Directions in the order of their codes in a packed record, `0` standing for no direction.
+const DIRECTIONS: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+/// Bit of a packed record set when the step reached its cell for the first time.
+const FIRST_VISIT: u8 = 0x80;
+
+/// Fewest steps between two snapshots of a run.
+pub const SNAPSHOT_INTERVAL: usize = 256;
+/// Most steps between two snapshots of a run, so that undoing a step never replays more.
+pub const MAX_SNAPSHOT_INTERVAL: usize = 1 << 16;
+/// Cells of maze the snapshots of a run copy at most, in total.
+pub const SNAPSHOT_CELLS: usize = 1 << 26;
+/// Bounds of the number of snapshots kept, whatever the size of the maze.
+pub const SNAPSHOTS: (usize, usize) = (4, 64);
+
+/// State of the solver and of its maze after some steps, to replay the run from.
+#[derive(Debug)]
+struct Snapshot {
+    solver: Box<dyn Solver>,
+    maze: Maze,
+}
+
+/// Timeline of a solver run that can be moved through in both directions.
+///
+/// Solvers are deterministic, so going back to an earlier step restores the closest
+/// snapshot before it and replays the run from there, and the recorded steps after the
+/// cursor are kept for scrubbing. Snapshots are taken every `interval` steps, which grows
+/// with the maze up to `MAX_SNAPSHOT_INTERVAL`. Each one copies the maze, so only a few
+/// are kept: the ones far from the cursor are dropped first, and going back replays the
+/// run from the closest one left, taking snapshots again on the way.
+#[derive(Debug)]
+pub struct History {
+    /// Index of the cell reached by each step.
+    cells: Vec<u32>,
+    /// Code of the direction after each step, with `FIRST_VISIT` when the cell was new.
+    moves: Vec<u8>,
+    /// Width of the maze, to turn cell indices back into positions.
+    width: usize,
+    cursor: usize,
+    /// Snapshots by the number of steps played before they were taken.
+    snapshots: BTreeMap<usize, Snapshot>,
+    interval: usize,
+    /// Most snapshots kept at once.
+    capacity: usize,
+}
+
+impl History {
+    pub fn new(solver: &dyn Solver) -> Self {
+        let (width, cells) = {
+            let maze = solver.maze().borrow();
+            (maze.width, maze.width * maze.height)
+        };
+        History {
+            cells: Vec::new(),
+            moves: Vec::new(),
+            width,
+            cursor: 0,
+            snapshots: BTreeMap::new(),
+            interval: cells.clamp(SNAPSHOT_INTERVAL, MAX_SNAPSHOT_INTERVAL),
+            capacity: (SNAPSHOT_CELLS / cells).clamp(SNAPSHOTS.0, SNAPSHOTS.1),
+        }
+    }
+
+    /// Number of steps played so far.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Number of steps recorded, including the ones after the cursor.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Step recorded at `index`, counting from 0.
+    pub fn record(&self, index: usize) -> Option<StepRecord> {
+        let cell = *self.cells.get(index)? as usize;
+        let position = (cell / self.width, cell % self.width);
+        let code = self.moves[index];
+
+        Some(StepRecord {
+            position,
+            direction: (code & !FIRST_VISIT).checked_sub(1).map(|direction| DIRECTIONS[direction as usize]),
+            visited: (code & FIRST_VISIT != 0).then_some(position),
+        })
+    }
+
+    /// Last step played, `None` at the start of the run.
+    pub fn current(&self) -> Option<StepRecord> {
+        self.cursor.checked_sub(1).and_then(|index| self.record(index))
+    }
+
+    /// Plays the next step, recording it if it was never played before.
+    pub fn step(&mut self, solver: &mut dyn Solver) {
+        if solver.is_finished() {
+            return;
+        }
+
+        // Every step marks the cell it reaches, which was new if the count went up
+        let reached = solver.maze().borrow().visited_count();
+        solver.step();
+        let (cell, first_visit) = {
+            let maze = solver.maze().borrow();
+            (maze.index(solver.current_cell()) as u32, maze.visited_count() > reached)
+        };
+        let direction = solver.direction().map_or(0, |direction| {
+            DIRECTIONS.iter().position(|&other| other == direction).unwrap() as u8 + 1
+        });
+        let code = if first_visit { direction | FIRST_VISIT } else { direction };
+
+        if self.cursor < self.cells.len() {
+            self.cells[self.cursor] = cell;
+            self.moves[self.cursor] = code;
+        } else {
+            self.cells.push(cell);
+            self.moves.push(code);
+        }
+        self.cursor += 1;
+        self.snapshot(solver, self.cursor);
+    }
+
+    /// Takes a snapshot if the cursor is on a multiple of the interval, then drops the
+    /// snapshots that matter least to going back to `focus`.
+    fn snapshot(&mut self, solver: &dyn Solver, focus: usize) {
+        if !self.cursor.is_multiple_of(self.interval) || self.snapshots.contains_key(&self.cursor) {
+            return;
+        }
+        let maze = solver.maze().borrow().clone();
+        self.snapshots.insert(self.cursor, Snapshot { solver: solver.clone_box(), maze });
+
+        while self.snapshots.len() > self.capacity {
+            // Drop the snapshot leaving the smallest gap for its distance to the focus,
+            // so that they get sparser away from it
+            let steps: Vec<usize> = self.snapshots.keys().copied().collect();
+            let score = |index: usize| {
+                let previous = index.checked_sub(1).map_or(0, |previous| steps[previous]);
+                let next = steps.get(index + 1).copied().unwrap_or(self.len().max(steps[index]));
+                (next - previous) as f64 / (steps[index].abs_diff(focus) + 1) as f64
+            };
+            let dropped = (0..steps.len()).min_by(|&first, &second| score(first).total_cmp(&score(second))).unwrap();
+            self.snapshots.remove(&steps[dropped]);
+        }
+    }
+
+    /// Undoes the last step.
+    pub fn back(&mut self, solver: &mut Box<dyn Solver>) {
+        if self.cursor > 0 {
+            self.rewind_to(solver, self.cursor - 1);
+        }
+    }
+
+    /// Goes back to the state of the run after `step` steps.
+    pub fn rewind_to(&mut self, solver: &mut Box<dyn Solver>, step: usize) {
+        let step = step.min(self.len());
+        if step >= self.cursor {
+            while self.cursor < step {
+                self.step(solver.as_mut());
+            }
+            return;
+        }
+
+        match self.snapshots.range(..=step).next_back() {
+            Some((&taken, snapshot)) => {
+                *solver.maze().borrow_mut() = snapshot.maze.clone();
+                *solver = snapshot.solver.clone_box();
+                self.cursor = taken;
+            },
+            None => {
+                solver.reset();
+                self.cursor = 0;
+            },
+        }
+
+        while self.cursor < step {
+            solver.step();
+            self.cursor += 1;
+            self.snapshot(solver.as_ref(), step);
+        }
+    }
+
+    /// Goes back to the beginning of the run.
+    pub fn start(&mut self, solver: &mut Box<dyn Solver>) {
+        self.rewind_to(solver, 0);
+    }
+
+    /// Plays the run until the solver is finished or `budget` steps were played.
+    pub fn end(&mut self, solver: &mut dyn Solver, budget: usize) {
+        while !solver.is_finished() && self.cursor < budget {
+            self.step(solver);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::solver::{self, SolveOutcome, SolverOptions};
+    use crate::wall_follower::{Hand, WallFollower};
+
+    fn create_test_solver() -> (Rc<RefCell<Maze>>, Box<dyn Solver>) {
+        let mut maze = Maze::new(3, 3);
+
+        // Dead end at (1,0) before the corridor leading to the exit
+        maze.open_adjacent_wall((0, 0), (1, 0));
+        maze.open_adjacent_wall((0, 0), (0, 1));
+        maze.open_adjacent_wall((0, 1), (0, 2));
+        maze.open_adjacent_wall((0, 2), (1, 2));
+        maze.open_adjacent_wall((1, 2), (2, 2));
+
+        let maze = Rc::new(RefCell::new(maze));
+        let mut solver: Box<dyn Solver> = Box::new(WallFollower::new(maze.clone(), Hand::Right));
+        solver.reset();
+        (maze, solver)
+    }
+
+    #[test]
+    fn test_history_records_steps() {
+        let (_, mut solver) = create_test_solver();
+        let mut history = History::new(solver.as_ref());
+
+        history.step(solver.as_mut());
+
+        assert_eq!(history.cursor(), 1);
+        assert_eq!(history.current(), Some(StepRecord {
+            position: (0, 1),
+            direction: Some(Direction::East),
+            visited: Some((0, 1)),
+        }));
+    }
+
+    #[test]
+    fn test_history_back_restores_state() {
+        let (maze, mut solver) = create_test_solver();
+        let mut history = History::new(solver.as_ref());
+
+        history.step(solver.as_mut());
+        history.step(solver.as_mut());
+        let after_two_steps = maze.borrow().current_cell;
+        history.step(solver.as_mut());
+        history.back(&mut solver);
+
+        assert_eq!(history.cursor(), 2);
+        assert_eq!(history.len(), 3, "Undone steps should stay recorded");
+        assert_eq!(maze.borrow().current_cell, after_two_steps);
+        assert!(!maze.borrow().cell((1, 2)).visited, "The undone step should no longer be visited");
+    }
+
+    #[test]
+    fn test_history_start_and_end() {
+        let (maze, mut solver) = create_test_solver();
+        let mut history = History::new(solver.as_ref());
+
+        history.end(solver.as_mut(), 100);
+        let steps = history.cursor();
+        assert!(solver.is_finished());
+        assert_eq!(maze.borrow().current_cell, (2, 2));
+
+        history.start(&mut solver);
+        assert_eq!(history.cursor(), 0);
+        assert_eq!(history.current(), None);
+        assert_eq!(maze.borrow().current_cell, (0, 0));
+
+        history.end(solver.as_mut(), 100);
+        assert_eq!(history.cursor(), steps, "Replaying should lead to the same run");
+    }
+
+    #[test]
+    fn test_history_revisited_cells() {
+        // Dead end at (0,1): the right hand follower goes into it and back through the start
+        let mut maze = Maze::new(2, 2);
+        maze.open_adjacent_wall((0, 0), (0, 1));
+        maze.open_adjacent_wall((0, 0), (1, 0));
+        maze.open_adjacent_wall((1, 0), (1, 1));
+        let maze = Rc::new(RefCell::new(maze));
+        let mut solver: Box<dyn Solver> = Box::new(WallFollower::new(maze.clone(), Hand::Right));
+        solver.reset();
+        let mut history = History::new(solver.as_ref());
+
+        history.end(solver.as_mut(), 100);
+        assert_eq!(solver.outcome(), Some(SolveOutcome::Reached));
+        let visited: Vec<_> = (0..history.len()).map(|index| history.record(index).unwrap().visited).collect();
+        assert_eq!(visited, vec![Some((0, 1)), None, Some((1, 0)), Some((1, 1))], "Going back to the start visits nothing new");
+
+        history.back(&mut solver);
+        history.back(&mut solver);
+        history.step(solver.as_mut());
+        assert_eq!(history.current().unwrap().visited, Some((1, 0)), "Undone steps should visit their cells again");
+    }
+
+    /// Trémaux solver on a maze made of a single corridor winding through every row.
+    fn create_snake_solver(size: usize) -> (Rc<RefCell<Maze>>, Box<dyn Solver>) {
+        let mut maze = Maze::new(size, size);
+        for row in 0..size {
+            for col in 0..size - 1 {
+                maze.open_adjacent_wall((row, col), (row, col + 1));
+            }
+            if row + 1 < size {
+                let col = if row % 2 == 0 { size - 1 } else { 0 };
+                maze.open_adjacent_wall((row, col), (row + 1, col));
+            }
+        }
+        let maze = Rc::new(RefCell::new(maze));
+        let solver = solver::create("tremaux", maze.clone(), &SolverOptions::default()).unwrap();
+        (maze, solver)
+    }
+
+    #[test]
+    fn test_history_rewinds_from_snapshots() {
+        let (maze, mut solver) = create_snake_solver(12);
+        let mut history = History::new(solver.as_ref());
+        history.interval = 16;
+
+        history.end(solver.as_mut(), 10_000);
+        let steps = history.cursor();
+        assert!(steps > 2 * history.interval, "The run should go past several snapshots");
+        assert_eq!(history.snapshots.len(), steps / history.interval);
+        assert_eq!(history.snapshots.keys().next(), Some(&history.interval));
+
+        let target = history.interval * 2 + 10;
+        history.rewind_to(&mut solver, target);
+        assert_eq!(history.cursor(), target);
+        assert_eq!(solver.current_cell(), history.record(target - 1).unwrap().position);
+        assert_eq!(solver.passage_marks().len(), target, "Every walked passage of the snake should be marked once");
+        assert!(Rc::ptr_eq(solver.maze(), &maze), "The restored solver should still share the maze");
+
+        history.end(solver.as_mut(), 10_000);
+        assert_eq!(history.cursor(), steps, "Replaying from a snapshot should lead to the same run");
+        assert_eq!(solver.outcome(), Some(SolveOutcome::Reached));
+    }
+
+    #[test]
+    fn test_history_keeps_few_snapshots() {
+        let (maze, mut solver) = create_snake_solver(20);
+        let mut history = History::new(solver.as_ref());
+        history.interval = 16;
+        history.capacity = 4;
+
+        history.end(solver.as_mut(), 10_000);
+        let steps = history.cursor();
+        assert_eq!(history.snapshots.len(), 4);
+        assert!(history.snapshots.keys().any(|&taken| taken + history.interval > steps), "The snapshots near the cursor should be kept");
+
+        for target in (steps - 40..steps).rev() {
+            history.back(&mut solver);
+            assert_eq!(history.cursor(), target);
+            assert_eq!(maze.borrow().current_cell, history.record(target - 1).unwrap().position);
+            let closest = history.snapshots.range(..=target).next_back().map_or(0, |(&taken, _)| taken);
+            assert!(target - closest < history.interval, "Undoing a step should only replay from a close snapshot");
+        }
+        assert!(history.snapshots.len() <= 4);
+
+        history.rewind_to(&mut solver, 5);
+        assert_eq!(maze.borrow().visited_count(), 6);
+        history.end(solver.as_mut(), 10_000);
+        assert_eq!(history.cursor(), steps);
+        assert_eq!(solver.outcome(), Some(SolveOutcome::Reached));
+    }
+}
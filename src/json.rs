@@ -0,0 +1,367 @@
+//! Versioned JSON format of mazes and solver runs, built with the `json` feature.
+//!
+//! A maze is written as an object carrying the version of the schema, [`SCHEMA_VERSION`]:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "width": 2,
+//!   "height": 1,
+//!   "seed": 42,
+//!   "generator": "backtracker",
+//!   "start": [0, 0],
+//!   "exit": [0, 1],
+//!   "cells": [[
+//!     {"cost": 1, "top_wall": true, "bottom_wall": true, "left_wall": true, "right_wall": false},
+//!     {"cost": 1, "top_wall": true, "bottom_wall": true, "left_wall": false, "right_wall": true}
+//!   ]]
+//! }
+//! ```
+//!
+//! Cells are given row by row and positions as `[row, column]`. `seed` and `generator` are
+//! `null` for mazes built by hand, and cells only carry `visited` or `filled` when they are set.
+//! Costs go from 1 to 255, mazes with other costs are rejected.
+//!
+//! A [`Trace`] records a whole solver run on a maze, so that it can be replayed: every step
+//! moves the solver to `cell`, which is then visited, or filled when `filled` is set.
+
+use std::fmt;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use crate::cell::Cell;
+use crate::direction::Direction;
+use crate::generator::GENERATORS;
+use crate::maze::{CellsError, Maze};
+use crate::solver::{Passage, SolveOutcome, Solver};
+
+/// Version of the schema written by this crate, bumped on every breaking change of the format.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Reason why a maze read from JSON can't be used.
+#[derive(Clone, PartialEq, Debug)]
+pub enum SchemaError {
+    UnsupportedVersion(u32),
+    /// The rows of cells don't match `width` and `height`.
+    WrongDimensions,
+    /// The start or the exit, named by the first field, lies outside of the maze.
+    OutsideCell(&'static str, (usize, usize)),
+    UnknownGenerator(String),
+    Cells(CellsError),
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::UnsupportedVersion(version) => {
+                write!(f, "unsupported schema version {}, expected {}", version, SCHEMA_VERSION)
+            }
+            SchemaError::WrongDimensions => write!(f, "the cells don't match the width and height of the maze"),
+            SchemaError::OutsideCell(name, (row, column)) => {
+                write!(f, "the {} ({}, {}) is outside of the maze", name, row, column)
+            }
+            SchemaError::UnknownGenerator(name) => write!(f, "unknown generator {}", name),
+            SchemaError::Cells(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+/// Serialized form of a [`Maze`], which is converted from and into it.
+///
+/// `Maze` can't derive the serde traits itself, its generator name being a `&'static str`.
+#[derive(Serialize, Deserialize)]
+pub struct MazeDocument {
+    pub version: u32,
+    pub width: usize,
+    pub height: usize,
+    pub seed: Option<u64>,
+    pub generator: Option<String>,
+    pub start: (usize, usize),
+    pub exit: (usize, usize),
+    pub cells: Vec<Vec<Cell>>,
+}
+
+impl From<Maze> for MazeDocument {
+    fn from(maze: Maze) -> Self {
+        MazeDocument {
+            version: SCHEMA_VERSION,
+            width: maze.width,
+            height: maze.height,
+            seed: maze.seed,
+            generator: maze.generator.map(str::to_string),
+            start: maze.start,
+            exit: maze.exit,
+            cells: (0..maze.height).map(|i| (0..maze.width).map(|j| maze.cell((i, j))).collect()).collect(),
+        }
+    }
+}
+
+impl TryFrom<MazeDocument> for Maze {
+    type Error = SchemaError;
+
+    fn try_from(document: MazeDocument) -> Result<Self, Self::Error> {
+        if document.version != SCHEMA_VERSION {
+            return Err(SchemaError::UnsupportedVersion(document.version));
+        }
+        if document.width == 0
+            || document.cells.len() != document.height
+            || document.cells.iter().any(|row| row.len() != document.width)
+        {
+            return Err(SchemaError::WrongDimensions);
+        }
+        let generator = match document.generator {
+            Some(name) => Some(
+                GENERATORS.iter().find(|&&generator| generator == name).copied()
+                    .ok_or(SchemaError::UnknownGenerator(name))?,
+            ),
+            None => None,
+        };
+
+        let mut maze = Maze::from_cells(&document.cells).map_err(SchemaError::Cells)?;
+        maze.seed = document.seed;
+        maze.generator = generator;
+        for (name, cell) in [("start", document.start), ("exit", document.exit)] {
+            if !maze.contains(cell) {
+                return Err(SchemaError::OutsideCell(name, cell));
+            }
+        }
+
+        maze.start = document.start;
+        maze.exit = document.exit;
+        maze.current_cell = maze.start;
+        Ok(maze)
+    }
+}
+
+impl Serialize for Maze {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        MazeDocument::from(self.clone()).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Maze {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let document = MazeDocument::deserialize(deserializer)?;
+        Maze::try_from(document).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Writes `maze` as pretty-printed JSON.
+pub fn to_json(maze: &Maze) -> String {
+    serde_json::to_string_pretty(maze).expect("a maze can always be serialized")
+}
+
+/// Reads a maze written as JSON, checking its version and that its walls are consistent.
+pub fn from_json(text: &str) -> serde_json::Result<Maze> {
+    serde_json::from_str(text)
+}
+
+/// Snapshot of what a solver knows at some point of its run.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SolverState {
+    pub solver: String,
+    pub current_cell: (usize, usize),
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direction: Option<Direction>,
+    /// How the run ended, `None` while the solver can still make progress.
+    pub outcome: Option<SolveOutcome>,
+    pub path: Vec<(usize, usize)>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub passage_marks: Vec<(Passage, u8)>,
+    pub visited: Vec<(usize, usize)>,
+    pub filled: Vec<(usize, usize)>,
+}
+
+impl SolverState {
+    pub fn capture(solver: &dyn Solver) -> Self {
+        let maze = solver.maze().borrow();
+        let cells = |flag: fn(&Cell) -> bool| {
+            (0..maze.height)
+                .flat_map(|i| (0..maze.width).map(move |j| (i, j)))
+                .filter(|&(i, j)| flag(&maze.cell((i, j))))
+                .collect()
+        };
+
+        SolverState {
+            solver: solver.name().to_string(),
+            current_cell: maze.current_cell,
+            direction: solver.direction(),
+            outcome: solver.outcome(),
+            path: solver.path(),
+            passage_marks: solver.passage_marks(),
+            visited: cells(|cell| cell.visited),
+            filled: cells(|cell| cell.filled),
+        }
+    }
+}
+
+/// One step of a solver run.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TraceStep {
+    /// Cell the solver is on after the step.
+    pub cell: (usize, usize),
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub direction: Option<Direction>,
+    /// Whether the step filled `cell` instead of visiting it.
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub filled: bool,
+}
+
+/// Full run of a solver on a maze, from the start until it finished or ran out of steps.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Trace {
+    pub version: u32,
+    /// Maze the solver ran on, without any visited cell.
+    pub maze: Maze,
+    pub steps: Vec<TraceStep>,
+    pub outcome: SolveOutcome,
+    /// State of the solver after the last step.
+    pub state: SolverState,
+}
+
+impl Trace {
+    /// Runs `solver` from the start for at most `budget` steps, recording every step.
+    pub fn record(solver: &mut dyn Solver, budget: usize) -> Trace {
+        let mut maze = solver.maze().borrow().clone();
+        maze.reset_visited_cells();
+        maze.current_cell = maze.start;
+        solver.reset();
+
+        let mut steps = Vec::new();
+        let outcome = loop {
+            if let Some(outcome) = solver.outcome() {
+                break outcome;
+            }
+            if steps.len() >= budget {
+                break SolveOutcome::BudgetExceeded;
+            }
+            solver.step();
+            let cell = solver.current_cell();
+            let filled = solver.maze().borrow().is_filled(cell);
+            steps.push(TraceStep { cell, direction: solver.direction(), filled });
+        };
+
+        Trace { version: SCHEMA_VERSION, maze, steps, outcome, state: SolverState::capture(solver) }
+    }
+
+    /// Writes the trace as JSON, on a single line since traces get long.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a trace can always be serialized")
+    }
+
+    /// Reads a trace written as JSON.
+    pub fn from_json(text: &str) -> serde_json::Result<Trace> {
+        let trace: Trace = serde_json::from_str(text)?;
+        if trace.version != SCHEMA_VERSION {
+            return Err(serde::de::Error::custom(SchemaError::UnsupportedVersion(trace.version)));
+        }
+        Ok(trace)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use super::*;
+    use crate::generator::backtracker::Backtracker;
+    use crate::solver::{self, step_budget, SolverOptions};
+
+    fn create_test_maze() -> Maze {
+        let mut maze = Maze::new(3, 2);
+        maze.open_adjacent_wall((0, 0), (0, 1));
+        maze.open_adjacent_wall((0, 1), (0, 2));
+        maze.open_adjacent_wall((0, 2), (1, 2));
+        maze.open_adjacent_wall((1, 2), (1, 1));
+        maze.open_adjacent_wall((1, 1), (1, 0));
+        maze.exit = (1, 0);
+        maze
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut maze = Maze::generate_weighted_with(&mut Backtracker::default(), 9, 6, 7);
+        maze.start = (2, 3);
+        maze.reset_visited_cells();
+
+        let loaded = from_json(&to_json(&maze)).unwrap();
+
+        assert!(loaded.cells().eq(maze.cells()));
+        assert_eq!((loaded.width, loaded.height), (9, 6));
+        assert_eq!((loaded.start, loaded.exit), (maze.start, maze.exit));
+        assert_eq!(loaded.current_cell, maze.start);
+        assert_eq!(loaded.seed, Some(7));
+        assert_eq!(loaded.generator, Some("backtracker"));
+    }
+
+    #[test]
+    fn test_json_schema() {
+        let json: serde_json::Value = serde_json::from_str(&to_json(&create_test_maze())).unwrap();
+
+        assert_eq!(json["version"], SCHEMA_VERSION);
+        assert_eq!(json["exit"], serde_json::json!([1, 0]));
+        assert_eq!(json["seed"], serde_json::Value::Null);
+        assert_eq!(json["cells"][0][0]["right_wall"], false);
+        assert!(json["cells"][0][0].get("visited").is_none(), "Flags are only written when set");
+    }
+
+    #[test]
+    fn test_json_rejects_invalid_mazes() {
+        let valid: serde_json::Value = serde_json::from_str(&to_json(&create_test_maze())).unwrap();
+        let load = |change: fn(&mut serde_json::Value)| {
+            let mut json = valid.clone();
+            change(&mut json);
+            from_json(&json.to_string()).unwrap_err().to_string()
+        };
+
+        assert!(load(|json| json["version"] = 2.into()).contains("unsupported schema version 2"));
+        assert!(load(|json| json["width"] = 4.into()).contains("width and height"));
+        assert!(load(|json| json["start"] = serde_json::json!([2, 0])).contains("start (2, 0)"));
+        assert!(load(|json| json["generator"] = "unknown".into()).contains("unknown generator"));
+        assert!(load(|json| json["cells"][0][0]["right_wall"] = true.into()).contains("don't match"));
+        assert!(load(|json| json["cells"][1][0]["cost"] = 1000.into()).contains("cost 1000 of (1, 0) is above the maximum of 255"));
+        assert!(load(|json| json["cells"][1][0]["cost"] = 0.into()).contains("cost of (1, 0) is 0"));
+        assert!(load(|json| {
+            json["height"] = 0.into();
+            json["cells"] = serde_json::json!([]);
+        }).contains("no cell"));
+    }
+
+    #[test]
+    fn test_trace_replays_the_run() {
+        let maze = Rc::new(RefCell::new(create_test_maze()));
+        let mut solver = solver::create("right-hand", maze.clone(), &SolverOptions::default()).unwrap();
+        solver.step();
+        let budget = step_budget(&maze.borrow());
+
+        let trace = Trace::record(solver.as_mut(), budget);
+
+        assert_eq!(trace.outcome, SolveOutcome::Reached);
+        assert!(trace.maze.cells().all(|(_, cell)| !cell.visited));
+        let cells: Vec<(usize, usize)> = trace.steps.iter().map(|step| step.cell).collect();
+        assert_eq!(cells, vec![(0, 1), (0, 2), (1, 2), (1, 1), (1, 0)], "The run restarts from the start");
+        assert_eq!(trace.steps[2].direction, Some(Direction::South));
+        assert_eq!(trace.state.current_cell, (1, 0));
+        assert_eq!(trace.state.visited.len(), 6);
+
+        let loaded = Trace::from_json(&trace.to_json()).unwrap();
+        assert_eq!(loaded.steps, trace.steps);
+        assert_eq!(loaded.state, trace.state);
+    }
+
+    #[test]
+    fn test_trace_of_a_filling_solver() {
+        let mut maze = create_test_maze();
+        maze.exit = (0, 2);
+        let maze = Rc::new(RefCell::new(maze));
+        let mut solver = solver::create("dead-end-filling", maze.clone(), &SolverOptions::default()).unwrap();
+
+        let trace = Trace::record(solver.as_mut(), 2);
+
+        assert_eq!(trace.outcome, SolveOutcome::BudgetExceeded);
+        assert_eq!(trace.steps.len(), 2);
+        assert_eq!(trace.state.outcome, None);
+        assert_eq!(trace.steps[0], TraceStep { cell: (1, 0), direction: None, filled: true });
+        assert_eq!(trace.state.filled, vec![(1, 0), (1, 1)]);
+    }
+}
@@ -0,0 +1,48 @@
+//! Maze generation and step-by-step maze solving algorithms.
+//!
+//! Mazes are generated from a seed by one of the [`generator::GENERATORS`], and solved
+//! by one of the [`solver::SOLVERS`], one step at a time:
+//!
+//! ```
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//! use pathfinder::{generator, solver, Maze, SolveOutcome, SolverOptions};
+//!
+//! let mut generator = generator::create("kruskal").unwrap();
+//! let maze = Maze::generate_with(generator.as_mut(), 20, 10, 42);
+//! let maze = Rc::new(RefCell::new(maze));
+//!
+//! let mut solver = solver::create("astar", maze.clone(), &SolverOptions::default()).unwrap();
+//! assert_eq!(solver.automatic_execution(), SolveOutcome::Reached);
+//! assert_eq!(maze.borrow().current_cell, maze.borrow().exit);
+//! ```
+//!
+//! The terminal frontend lives in the `pathfinder` binary, built with the `tui` feature.
+
+pub mod ascii;
+pub mod astar;
+pub mod bench;
+pub mod bfs;
+pub mod cell;
+pub mod dijkstra;
+pub mod direction;
+pub mod filling;
+pub mod generator;
+pub mod history;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod maze;
+pub mod pledge;
+pub mod race;
+pub mod record;
+pub mod render;
+pub mod solver;
+pub mod tremaux;
+pub mod viewport;
+pub mod wall_follower;
+
+pub use cell::Cell;
+pub use direction::Direction;
+pub use generator::{Generation, Generator};
+pub use maze::Maze;
+pub use solver::{SolveOutcome, Solver, SolverOptions};
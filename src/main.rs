@@ -1,41 +1,423 @@
 use std::cell::RefCell;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
 use std::rc::Rc;
 use crate::app::App;
-use crate::maze::Maze;
-use clap::{arg, command, value_parser};
-use color_eyre::eyre::eyre;
+use crate::cast::CastRecorder;
+use pathfinder::ascii;
+use pathfinder::astar::{self, Heuristic};
+use pathfinder::bench::{self, BenchConfig};
+use pathfinder::generator::{self, Generation};
+use pathfinder::json::{self, Trace};
+use pathfinder::maze::{Maze, MAX_CELLS};
+use pathfinder::race::{self, Race};
+use pathfinder::record;
+use pathfinder::render::{self, RenderOptions, Rgb, Scene, MAX_PIXELS};
+use pathfinder::solver::{self, step_budget, SolverOptions};
+use clap::{arg, command, value_parser, Arg, ArgMatches, Command};
+use clap::builder::PossibleValuesParser;
+use color_eyre::eyre::{eyre, WrapErr};
 use color_eyre::Result;
+use ratatui::crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use ratatui::crossterm::execute;
 
-mod cell;
-mod maze;
 mod app;
-mod right_hand;
+mod cast;
 
 fn main() -> Result<()>{
     let matches = command!()
         .author("Schaeffer Divino, divinoschaeffer@gmail.com")
-        .arg(arg!(-d --dimensions <DIM> "Dimensions of the maze")
-            .required(true)
-            .value_parser(value_parser!(usize))
-            .num_args(2))
+        .args(maze_args())
+        .arg(arg!(-s --solver <NAME> "Solving algorithm to start with")
+            .value_parser(PossibleValuesParser::new(solver::SOLVERS))
+            .default_value(solver::SOLVERS[0]))
+        .arg(heuristic_arg())
+        .arg(arg!(--"animate-generation" "Show the maze being generated step by step before solving it")
+            .conflicts_with("load"))
+        .arg(arg!(--save <FILE> "Save the maze to a text or .json file before solving it")
+            .conflicts_with("animate-generation"))
+        .arg(arg!(--trace <FILE> "Run the solver without the TUI and write the trace of the run as JSON")
+            .conflicts_with_all(["animate-generation", "race"]))
+        .arg(arg!(--record <FILE> "Record the session as an asciicast v2 file")
+            .conflicts_with("trace"))
+        .arg(arg!(--race <NAME> "Race two to four solvers side by side on copies of the maze")
+            .value_parser(PossibleValuesParser::new(solver::SOLVERS))
+            .num_args(race::MIN_RACERS..=race::MAX_RACERS))
+        .subcommand_negates_reqs(true)
+        .subcommand(Command::new("bench")
+            .about("Runs every solver on seeded mazes without the TUI and prints the measures")
+            .arg(arg!(--size <SIZE> "Sizes of the mazes, as WIDTHxHEIGHT")
+                .value_parser(parse_size)
+                .num_args(1..)
+                .default_value("20x20"))
+            .arg(arg!(-g --generator <NAME> "Maze generation algorithms")
+                .value_parser(PossibleValuesParser::new(generator::GENERATORS))
+                .num_args(1..)
+                .default_value(generator::GENERATORS[0]))
+            .arg(arg!(-n --count <COUNT> "Number of mazes for each size and generator")
+                .value_parser(value_parser!(usize))
+                .default_value("10"))
+            .arg(arg!(--seed <SEED> "Seed of the first maze, the next ones use the following seeds")
+                .value_parser(value_parser!(u64))
+                .default_value("0"))
+            .arg(arg!(-w --weighted "Add loops and mud or water regions with a higher traversal cost"))
+            .arg(heuristic_arg())
+            .arg(arg!(-f --format <FORMAT> "Format of the measures")
+                .value_parser(PossibleValuesParser::new(bench::FORMATS))
+                .default_value(bench::FORMATS[0]))
+            .arg(arg!(-o --output <FILE> "File the measures are written to, standard output if omitted")))
+        .subcommand(Command::new("render")
+            .about("Draws the maze, and optionally how a solver went through it, to an SVG or PNG file")
+            .args(maze_args())
+            .arg(arg!(-s --solver <NAME> "Solving algorithm drawn with --path and --heatmap")
+                .value_parser(PossibleValuesParser::new(solver::SOLVERS))
+                .default_value("bfs"))
+            .arg(heuristic_arg())
+            .arg(arg!(--path "Draw the path found by the solver"))
+            .arg(arg!(--heatmap "Shade the cells by the number of times the solver visited them"))
+            .arg(arg!(--"cell-size" <PIXELS> "Width and height of a cell")
+                .value_parser(value_parser!(u32).range(1..))
+                .default_value("20"))
+            .arg(arg!(--"wall-thickness" <PIXELS> "Thickness of the walls")
+                .value_parser(value_parser!(u32).range(1..))
+                .default_value("2"))
+            .arg(color_arg("background-color", "Colour of the background", "#ffffff"))
+            .arg(color_arg("wall-color", "Colour of the walls", "#000000"))
+            .arg(color_arg("path-color", "Colour of the path", "#32a046"))
+            .arg(color_arg("heatmap-color", "Colour of the most visited cells", "#e65028"))
+            .arg(arg!(-o --output <FILE> "File the picture is written to, as PNG if it ends with .png and SVG otherwise")
+                .required(true)))
+        .subcommand(Command::new("record")
+            .about("Runs a solver without the TUI and records it as an animated GIF")
+            .args(maze_args())
+            .arg(arg!(-s --solver <NAME> "Solving algorithm to record")
+                .value_parser(PossibleValuesParser::new(solver::SOLVERS))
+                .default_value(solver::SOLVERS[0]))
+            .arg(heuristic_arg())
+            .arg(arg!(--every <STEPS> "Number of steps between two frames")
+                .value_parser(value_parser!(u64).range(1..))
+                .default_value("1"))
+            .arg(arg!(--delay <MS> "Time each frame is shown, in milliseconds")
+                .value_parser(value_parser!(u32))
+                .default_value("50"))
+            .arg(arg!(--"cell-size" <PIXELS> "Width and height of a cell")
+                .value_parser(value_parser!(u32).range(2..))
+                .default_value("12"))
+            .arg(arg!(-o --output <FILE> "File the animation is written to")
+                .visible_alias("out")
+                .required(true)))
         .get_matches();
 
-    let (width, height) = if let Some(mut dims) = matches.get_many::<usize>("dimensions") {
-        (*dims.next().unwrap(), *dims.next().unwrap())
+    if let Some(matches) = matches.subcommand_matches("bench") {
+        color_eyre::install()?;
+        return bench(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("render") {
+        color_eyre::install()?;
+        return render(matches);
+    }
+    if let Some(matches) = matches.subcommand_matches("record") {
+        color_eyre::install()?;
+        return record(matches);
+    }
+
+    color_eyre::install()?;
+    let longest_path = matches.get_flag("longest-path");
+    let mut generation = None;
+    let mut maze = if matches.get_flag("animate-generation") {
+        let (width, height) = dimensions(&matches)?;
+        let seed = matches.get_one::<u64>("seed").copied().unwrap_or_else(rand::random);
+        let generator = generator::create(matches.get_one::<String>("generator").unwrap()).unwrap();
+        let mut maze = Maze::new(width, height);
+        let mut animated = Generation::new(generator, &mut maze, seed, matches.get_flag("weighted"));
+        if longest_path {
+            animated = animated.with_longest_path();
+        }
+        generation = Some(animated);
+        maze
     } else {
-        (20, 20)
+        load_or_generate(&matches)?
     };
 
-    if width == 0 || height == 0  || width > 1000 || height > 1000 {
-        return Err(eyre!("Invalid dimensions"));
+    place_start_and_exit(&matches, &mut maze)?;
+    if longest_path && generation.is_none() {
+        maze.place_on_longest_path();
+    }
+    maze.reset_visited_cells();
+    if let Some(path) = matches.get_one::<String>("save") {
+        let text = if is_json(path) { json::to_json(&maze) } else { ascii::to_ascii(&maze) };
+        fs::write(path, text).wrap_err_with(|| format!("Failed to save the maze to {}", path))?;
     }
+    let maze = Rc::new(RefCell::new(maze));
+    let solver_name = matches.get_one::<String>("solver").unwrap();
+    let heuristic_name = matches.get_one::<String>("heuristic").unwrap();
+    let options = SolverOptions { heuristic: Heuristic::from_name(heuristic_name).unwrap() };
+    let mut solver = solver::create(solver_name, maze.clone(), &options).unwrap();
+    if let Some(path) = matches.get_one::<String>("trace") {
+        let budget = step_budget(&maze.borrow());
+        let trace = Trace::record(solver.as_mut(), budget);
+        return fs::write(path, trace.to_json()).wrap_err_with(|| format!("Failed to write the trace to {}", path));
+    }
+    let race = matches.get_many::<String>("race").map(|names| {
+        let names: Vec<&str> = names.map(String::as_str).collect();
+        Race::new(&names, &maze.borrow(), &options).unwrap()
+    });
+
+    let recorder = match matches.get_one::<String>("record") {
+        Some(path) => Some(CastRecorder::create(path).wrap_err_with(|| format!("Failed to create {}", path))?),
+        None => None,
+    };
 
-    color_eyre::install()?;
     let mut terminal = ratatui::init();
-    let mut maze: Maze = Maze::generate_maze(width,height);
-    maze.reset_visited_cells();
-    let result = App::new(Rc::new(RefCell::new(maze))).run(&mut terminal);
+    // The mouse only zooms and moves the camera, the TUI still works in terminals without it
+    let _ = execute!(io::stdout(), EnableMouseCapture);
+    let mut app = App::new(maze, solver, options);
+    if let Some(generation) = generation {
+        app = app.animate_generation(generation);
+    }
+    if let Some(race) = race {
+        app = app.race(race);
+    }
+    if let Some(recorder) = recorder {
+        app = app.record(recorder);
+    }
+    let result = app.run(&mut terminal);
 
+    let _ = execute!(io::stdout(), DisableMouseCapture);
     ratatui::restore();
     result
 }
+
+/// Runs the `bench` subcommand, writing the measures without starting the TUI.
+fn bench(matches: &ArgMatches) -> Result<()> {
+    let heuristic_name = matches.get_one::<String>("heuristic").unwrap();
+    let config = BenchConfig {
+        sizes: matches.get_many::<(usize, usize)>("size").unwrap().copied().collect(),
+        generators: matches.get_many::<String>("generator").unwrap().cloned().collect(),
+        count: *matches.get_one::<usize>("count").unwrap(),
+        seed: *matches.get_one::<u64>("seed").unwrap(),
+        weighted: matches.get_flag("weighted"),
+        options: SolverOptions { heuristic: Heuristic::from_name(heuristic_name).unwrap() },
+    };
+    let results = bench::run(&config).ok_or_else(|| eyre!("Unknown generator"))?;
+
+    let mut out: BufWriter<Box<dyn Write>> = match matches.get_one::<String>("output") {
+        Some(path) => BufWriter::new(Box::new(File::create(path)?)),
+        None => BufWriter::new(Box::new(io::stdout().lock())),
+    };
+    match matches.get_one::<String>("format").unwrap().as_str() {
+        "json" => bench::write_json(&results, &mut out)?,
+        _ => bench::write_csv(&results, &mut out)?,
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Runs the `render` subcommand, drawing the maze to a file without starting the TUI.
+fn render(matches: &ArgMatches) -> Result<()> {
+    let mut maze = load_or_generate(matches)?;
+    place_start_and_exit(matches, &mut maze)?;
+    if matches.get_flag("longest-path") {
+        maze.place_on_longest_path();
+    }
+    maze.reset_visited_cells();
+
+    let mut scene = Scene::new(&maze);
+    if matches.get_flag("path") || matches.get_flag("heatmap") {
+        let heuristic_name = matches.get_one::<String>("heuristic").unwrap();
+        let options = SolverOptions { heuristic: Heuristic::from_name(heuristic_name).unwrap() };
+        let solver_name = matches.get_one::<String>("solver").unwrap();
+        let solved = Rc::new(RefCell::new(maze.clone()));
+        let mut solver = solver::create(solver_name, solved.clone(), &options).unwrap();
+        let counts = render::visit_counts(solver.as_mut(), step_budget(&maze));
+        if matches.get_flag("path") {
+            scene = scene.path(solver.path());
+        }
+        if matches.get_flag("heatmap") {
+            scene = scene.heat(counts);
+        }
+    }
+
+    let options = RenderOptions {
+        cell_size: *matches.get_one::<u32>("cell-size").unwrap(),
+        wall_thickness: *matches.get_one::<u32>("wall-thickness").unwrap(),
+        background: *matches.get_one::<Rgb>("background-color").unwrap(),
+        wall: *matches.get_one::<Rgb>("wall-color").unwrap(),
+        path: *matches.get_one::<Rgb>("path-color").unwrap(),
+        heat: *matches.get_one::<Rgb>("heatmap-color").unwrap(),
+        ..RenderOptions::default()
+    };
+    if options.wall_thickness >= options.cell_size {
+        return Err(eyre!("The walls must be thinner than the cells"));
+    }
+    check_picture_size(&maze, &options)?;
+
+    let path = matches.get_one::<String>("output").unwrap();
+    if path.ends_with(".png") {
+        let mut out = BufWriter::new(File::create(path).wrap_err_with(|| format!("Failed to create {}", path))?);
+        render::rasterize(&scene, &options).write_png(&mut out)?;
+        out.flush()?;
+    } else {
+        fs::write(path, render::to_svg(&scene, &options)).wrap_err_with(|| format!("Failed to write {}", path))?;
+    }
+    Ok(())
+}
+
+/// Runs the `record` subcommand, stepping the solver without the TUI and encoding the frames.
+fn record(matches: &ArgMatches) -> Result<()> {
+    let mut maze = load_or_generate(matches)?;
+    place_start_and_exit(matches, &mut maze)?;
+    if matches.get_flag("longest-path") {
+        maze.place_on_longest_path();
+    }
+    maze.reset_visited_cells();
+    let budget = step_budget(&maze);
+
+    let heuristic_name = matches.get_one::<String>("heuristic").unwrap();
+    let options = SolverOptions { heuristic: Heuristic::from_name(heuristic_name).unwrap() };
+    let solver_name = matches.get_one::<String>("solver").unwrap();
+    let mut solver = solver::create(solver_name, Rc::new(RefCell::new(maze)), &options).unwrap();
+
+    let cell_size = *matches.get_one::<u32>("cell-size").unwrap();
+    let options = RenderOptions { cell_size, wall_thickness: (cell_size / 6).max(1), ..RenderOptions::terminal() };
+    check_picture_size(&solver.maze().borrow(), &options)?;
+    let every = *matches.get_one::<u64>("every").unwrap() as usize;
+    let delay = *matches.get_one::<u32>("delay").unwrap();
+
+    let path = matches.get_one::<String>("output").unwrap();
+    let mut out = BufWriter::new(File::create(path).wrap_err_with(|| format!("Failed to create {}", path))?);
+    let frames = record::frames(solver.as_mut(), options, every, budget);
+    record::write_gif(frames, &options.palette(), delay, &mut out)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Fails when the picture of `maze` would have more than `MAX_PIXELS` pixels.
+fn check_picture_size(maze: &Maze, options: &RenderOptions) -> Result<()> {
+    if render::picture_size(maze, options).is_none() {
+        return Err(eyre!("The picture would have more than {} pixels, try a smaller cell size", MAX_PIXELS));
+    }
+    Ok(())
+}
+
+/// Arguments describing the maze to solve, shared by the commands that build one.
+fn maze_args() -> [Arg; 8] {
+    [
+        arg!(-d --dimensions <DIM> "Dimensions of the maze")
+            .required_unless_present("load")
+            .value_parser(value_parser!(usize))
+            .num_args(2),
+        arg!(--seed <SEED> "Seed of the maze generation, random if omitted")
+            .value_parser(value_parser!(u64)),
+        arg!(-g --generator <NAME> "Maze generation algorithm")
+            .value_parser(PossibleValuesParser::new(generator::GENERATORS))
+            .default_value(generator::GENERATORS[0]),
+        arg!(-w --weighted "Add loops and mud or water regions with a higher traversal cost"),
+        arg!(--load <FILE> "Load the maze from a text or .json file instead of generating it")
+            .conflicts_with_all(["dimensions", "seed", "generator", "weighted"]),
+        arg!(--start <CELL> "Start cell, as row,column")
+            .value_parser(parse_cell),
+        arg!(--exit <CELL> "Exit cell, as row,column")
+            .value_parser(parse_cell),
+        arg!(--"longest-path" "Place the start and the exit at the two ends of the longest path")
+            .conflicts_with_all(["start", "exit"]),
+    ]
+}
+
+fn heuristic_arg() -> Arg {
+    arg!(--heuristic <NAME> "Heuristic used by the A* solver")
+        .value_parser(PossibleValuesParser::new(astar::HEURISTICS))
+        .default_value(astar::HEURISTICS[0])
+}
+
+fn color_arg(name: &'static str, help: &'static str, default: &'static str) -> Arg {
+    Arg::new(name)
+        .long(name)
+        .value_name("COLOR")
+        .help(format!("{}, as #rrggbb", help))
+        .value_parser(|value: &str| render::parse_color(value).ok_or("expected a colour as #rrggbb"))
+        .default_value(default)
+}
+
+/// Width and height given with `--dimensions`.
+fn dimensions(matches: &ArgMatches) -> Result<(usize, usize)> {
+    let (width, height) = if let Some(mut dims) = matches.get_many::<usize>("dimensions") {
+        (*dims.next().unwrap(), *dims.next().unwrap())
+    } else {
+        (20, 20)
+    };
+
+    if !valid_dimensions(width, height) {
+        return Err(eyre!("Invalid dimensions, a maze has at most {} cells", MAX_CELLS));
+    }
+    Ok((width, height))
+}
+
+/// Loads the maze given with `--load`, or generates it from the other maze arguments.
+fn load_or_generate(matches: &ArgMatches) -> Result<Maze> {
+    if let Some(path) = matches.get_one::<String>("load") {
+        let text = fs::read_to_string(path).wrap_err_with(|| format!("Failed to read {}", path))?;
+        let maze = if is_json(path) {
+            json::from_json(&text).wrap_err_with(|| format!("Failed to load the maze from {}", path))?
+        } else {
+            ascii::from_ascii(&text).wrap_err_with(|| format!("Failed to load the maze from {}", path))?
+        };
+        return Ok(maze);
+    }
+
+    let (width, height) = dimensions(matches)?;
+    let seed = matches.get_one::<u64>("seed").copied().unwrap_or_else(rand::random);
+    let mut generator = generator::create(matches.get_one::<String>("generator").unwrap()).unwrap();
+    if matches.get_flag("weighted") {
+        Ok(Maze::generate_weighted_with(generator.as_mut(), width, height, seed))
+    } else {
+        Ok(Maze::generate_with(generator.as_mut(), width, height, seed))
+    }
+}
+
+/// Moves the start and the exit to the cells given with `--start` and `--exit`.
+fn place_start_and_exit(matches: &ArgMatches, maze: &mut Maze) -> Result<()> {
+    if let Some(&start) = matches.get_one::<(usize, usize)>("start") {
+        if !maze.contains(start) {
+            return Err(eyre!("Start cell is outside of the maze"));
+        }
+        maze.start = start;
+        maze.current_cell = start;
+    }
+    if let Some(&exit) = matches.get_one::<(usize, usize)>("exit") {
+        if !maze.contains(exit) {
+            return Err(eyre!("Exit cell is outside of the maze"));
+        }
+        maze.exit = exit;
+    }
+    Ok(())
+}
+
+/// Whether `path` names a JSON file rather than a plain-text one.
+fn is_json(path: &str) -> bool {
+    path.ends_with(".json")
+}
+
+/// Whether a maze of `width` by `height` cells can be built.
+fn valid_dimensions(width: usize, height: usize) -> bool {
+    width > 0 && height > 0 && width.checked_mul(height).is_some_and(|cells| cells <= MAX_CELLS)
+}
+
+/// Parses a maze size given as `WIDTHxHEIGHT`.
+fn parse_size(value: &str) -> Result<(usize, usize), String> {
+    let (width, height) = value.split_once('x').ok_or("expected a size as WIDTHxHEIGHT")?;
+    let width = width.trim().parse().map_err(|_| format!("invalid width: {}", width))?;
+    let height = height.trim().parse().map_err(|_| format!("invalid height: {}", height))?;
+    if !valid_dimensions(width, height) {
+        return Err(format!("invalid dimensions, a maze has at most {} cells", MAX_CELLS));
+    }
+    Ok((width, height))
+}
+
+/// Parses a cell given as `row,column`.
+fn parse_cell(value: &str) -> Result<(usize, usize), String> {
+    let (row, col) = value.split_once(',').ok_or("expected a cell as row,column")?;
+    let row = row.trim().parse().map_err(|_| format!("invalid row: {}", row))?;
+    let col = col.trim().parse().map_err(|_| format!("invalid column: {}", col))?;
+    Ok((row, col))
+}
@@ -1,95 +1,507 @@
-use rand::Rng;
-use crate::cell::Cell;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::fmt;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use crate::cell::{Cell, DEFAULT_COST, MAX_COST, MUD_COST, WATER_COST};
+use crate::direction::Direction;
+use crate::generator::backtracker::Backtracker;
+use crate::generator::Generator;
+
+/// Deterministic random number generator used to build mazes, so that a seed always gives the same maze.
+pub type MazeRng = ChaCha8Rng;
+
+/// Fixed number of bits packed into words.
+#[derive(Clone, PartialEq, Debug)]
+struct Bits {
+    words: Vec<u64>,
+}
+
+impl Bits {
+    fn new(len: usize, value: bool) -> Self {
+        Bits { words: vec![if value { u64::MAX } else { 0 }; len.div_ceil(64)] }
+    }
+
+    fn get(&self, index: usize) -> bool {
+        self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    fn set(&mut self, index: usize, value: bool) {
+        if value {
+            self.words[index / 64] |= 1 << (index % 64);
+        } else {
+            self.words[index / 64] &= !(1 << (index % 64));
+        }
+    }
+
+    fn clear(&mut self) {
+        self.words.fill(0);
+    }
+}
+
+/// Largest number of cells of a maze, so that a cell can be counted or indexed with a `u32`.
+pub const MAX_CELLS: usize = u32::MAX as usize;
+
+/// Order in which `Maze::neighbours` lists the cells around a cell.
+const NEIGHBOUR_ORDER: [Direction; 4] = [Direction::North, Direction::South, Direction::West, Direction::East];
 
-#[derive(Debug)]
+/// Distance of the cells a search hasn't reached yet.
+pub const UNREACHED: u32 = u32::MAX;
+
+/// A grid of cells separated by walls.
+///
+/// Every wall is stored once, as a bit shared by the two cells it separates: the
+/// horizontal walls are stored row by row, from the top border to the bottom one, and
+/// the vertical walls row by row, from the left border to the right one. The cells
+/// themselves are read through [`Maze::cell`].
+#[derive(Clone, Debug)]
 pub struct Maze {
     pub width: usize,
     pub height: usize,
-    pub cells: Vec<Vec<Cell>>,
-    pub path: Vec<(usize, usize)>,
+    /// `height + 1` rows of `width` walls, the top wall of (i, j) being at `i * width + j`.
+    horizontal_walls: Bits,
+    /// `height` rows of `width + 1` walls, the left wall of (i, j) being at `i * (width + 1) + j`.
+    vertical_walls: Bits,
+    visited: Bits,
+    filled: Bits,
+    /// Number of cells visited or filled, kept up to date so that it is read without counting.
+    reached: usize,
+    /// Traversal cost of every cell, empty as long as every cell has the default cost.
+    costs: Vec<u8>,
+    /// Last cell added to the maze by the generator, highlighted while the generation is animated.
+    pub last_carved: Option<(usize, usize)>,
+    /// Number of times the generator added a cell to the maze, or drew a wall along one.
+    pub carved: usize,
     pub current_cell: (usize, usize),
+    /// Cell the solvers start from, as (row, column).
+    pub start: (usize, usize),
+    /// Cell the solvers have to reach, as (row, column).
     pub exit: (usize, usize),
+    /// Seed the maze was generated from, `None` for mazes built by hand.
+    pub seed: Option<u64>,
+    /// Name of the algorithm the maze was generated with.
+    pub generator: Option<&'static str>,
+}
+
+/// Two sides of the same wall that disagree, e.g. the right wall of a cell being open
+/// while the left wall of its neighbour is closed.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct WallMismatch {
+    pub first: (usize, usize),
+    pub second: (usize, usize),
+}
+
+impl fmt::Display for WallMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the walls between cells ({}, {}) and ({}, {}) don't match",
+            self.first.0, self.first.1, self.second.0, self.second.1,
+        )
+    }
+}
+
+impl std::error::Error for WallMismatch {}
+
+/// Reason why rows of cells can't be turned into a maze.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CellsError {
+    /// There is no row, or the first row has no cell.
+    Empty,
+    /// The row, numbered by the field, doesn't have as many cells as the first one.
+    UnevenRow(usize),
+    Walls(WallMismatch),
+    /// The cell has a cost of 0, while every step has to cost at least 1 for A*'s heuristics.
+    CostTooLow((usize, usize)),
+    /// The cell has a cost above `MAX_COST`, which the maze can't store.
+    CostTooHigh((usize, usize), u32),
 }
 
+impl fmt::Display for CellsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CellsError::Empty => write!(f, "the maze has no cell"),
+            CellsError::UnevenRow(row) => write!(f, "row {} doesn't have as many cells as the first one", row),
+            CellsError::Walls(mismatch) => mismatch.fmt(f),
+            CellsError::CostTooLow((row, column)) => write!(f, "the cost of ({}, {}) is 0, costs start at 1", row, column),
+            CellsError::CostTooHigh((row, column), cost) => {
+                write!(f, "the cost {} of ({}, {}) is above the maximum of {}", cost, row, column, MAX_COST)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CellsError {}
+
 impl Maze {
+    /// Maze of `width` by `height` cells whose walls are all closed, to be solved from
+    /// the top-left cell to the bottom-right one.
+    ///
+    /// # Panics
+    ///
+    /// If `width` or `height` is 0.
     pub fn new(width: usize, height: usize) -> Maze {
-        let cells = (0..height)
-            .map(|_| (0..width).map(|_| Cell::default()).collect())
-            .collect();
-
+        assert!(width > 0 && height > 0, "A maze needs at least one cell, not {}x{}", width, height);
         Maze {
             width,
             height,
-            cells,
-            path: Vec::new(),
+            horizontal_walls: Bits::new((height + 1) * width, true),
+            vertical_walls: Bits::new(height * (width + 1), true),
+            visited: Bits::new(width * height, false),
+            filled: Bits::new(width * height, false),
+            reached: 0,
+            costs: Vec::new(),
+            last_carved: None,
+            carved: 0,
             current_cell: (0,0),
-            exit: (width - 1, height - 1),
+            start: (0, 0),
+            exit: (height - 1, width - 1),
+            seed: None,
+            generator: None,
         }
     }
 
-    pub fn get_neighbours(&self, i: usize, j: usize) -> Vec<(usize, usize)> {
-        let mut neighbours = Vec::new();
+    /// Builds a maze out of rows of cells, which must all be as long and whose walls must
+    /// agree with their neighbours'. Costs go from 1 to `MAX_COST`.
+    pub fn from_cells(cells: &[Vec<Cell>]) -> Result<Maze, CellsError> {
+        check_walls(cells)?;
+        for (i, row) in cells.iter().enumerate() {
+            for (j, cell) in row.iter().enumerate() {
+                match cell.cost {
+                    0 => return Err(CellsError::CostTooLow((i, j))),
+                    cost if cost > MAX_COST => return Err(CellsError::CostTooHigh((i, j), cost)),
+                    _ => {}
+                }
+            }
+        }
+        let height = cells.len();
+        let width = cells[0].len();
+
+        let mut maze = Maze::new(width, height);
+        for (i, row) in cells.iter().enumerate() {
+            for (j, cell) in row.iter().enumerate() {
+                let position = (i, j);
+                maze.set_wall(position, Direction::North, cell.top_wall);
+                maze.set_wall(position, Direction::South, cell.bottom_wall);
+                maze.set_wall(position, Direction::West, cell.left_wall);
+                maze.set_wall(position, Direction::East, cell.right_wall);
+                maze.set_visited(position, cell.visited);
+                maze.set_filled(position, cell.filled);
+                maze.set_cost(position, cell.cost);
+            }
+        }
+        Ok(maze)
+    }
+
+    /// Position of `cell` when the cells are laid out row by row, for storage indexed by cell.
+    pub fn index(&self, (row, col): (usize, usize)) -> usize {
+        row * self.width + col
+    }
+
+    /// Cell at `index` when the cells are laid out row by row, the inverse of `index`.
+    pub fn position(&self, index: usize) -> (usize, usize) {
+        (index / self.width, index % self.width)
+    }
+
+    /// Storage and index of the wall on the `direction` side of `cell`.
+    fn wall_bit(&self, (row, col): (usize, usize), direction: Direction) -> (bool, usize) {
+        match direction {
+            Direction::North => (true, row * self.width + col),
+            Direction::South => (true, (row + 1) * self.width + col),
+            Direction::West => (false, row * (self.width + 1) + col),
+            Direction::East => (false, row * (self.width + 1) + col + 1),
+        }
+    }
+
+    /// Whether there is a wall on the `direction` side of `cell`.
+    pub fn has_wall(&self, cell: (usize, usize), direction: Direction) -> bool {
+        match self.wall_bit(cell, direction) {
+            (true, index) => self.horizontal_walls.get(index),
+            (false, index) => self.vertical_walls.get(index),
+        }
+    }
+
+    /// Opens or closes the wall on the `direction` side of `cell`, for the neighbour on
+    /// the other side as well.
+    pub fn set_wall(&mut self, cell: (usize, usize), direction: Direction, wall: bool) {
+        match self.wall_bit(cell, direction) {
+            (true, index) => self.horizontal_walls.set(index, wall),
+            (false, index) => self.vertical_walls.set(index, wall),
+        }
+    }
+
+    /// Reads the walls, flags and cost of `cell`.
+    pub fn cell(&self, cell: (usize, usize)) -> Cell {
+        Cell {
+            visited: self.is_visited(cell),
+            filled: self.is_filled(cell),
+            cost: self.cost(cell),
+            top_wall: self.has_wall(cell, Direction::North),
+            bottom_wall: self.has_wall(cell, Direction::South),
+            left_wall: self.has_wall(cell, Direction::West),
+            right_wall: self.has_wall(cell, Direction::East),
+        }
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = ((usize, usize), Cell)> + '_ {
+        (0..self.height)
+            .flat_map(move |i| (0..self.width).map(move |j| (i, j)))
+            .map(|position| (position, self.cell(position)))
+    }
 
-        if i > 0 {
-            neighbours.push((i - 1, j));
+    pub fn is_visited(&self, cell: (usize, usize)) -> bool {
+        self.visited.get(self.index(cell))
+    }
+
+    pub fn set_visited(&mut self, cell: (usize, usize), visited: bool) {
+        let index = self.index(cell);
+        let was_reached = self.is_reached(index);
+        self.visited.set(index, visited);
+        self.count_reached(index, was_reached);
+    }
+
+    pub fn is_filled(&self, cell: (usize, usize)) -> bool {
+        self.filled.get(self.index(cell))
+    }
+
+    pub fn set_filled(&mut self, cell: (usize, usize), filled: bool) {
+        let index = self.index(cell);
+        let was_reached = self.is_reached(index);
+        self.filled.set(index, filled);
+        self.count_reached(index, was_reached);
+    }
+
+    /// Whether the cell at `index` is visited or filled.
+    fn is_reached(&self, index: usize) -> bool {
+        self.visited.get(index) || self.filled.get(index)
+    }
+
+    /// Updates `reached` after the flags of the cell at `index` changed.
+    fn count_reached(&mut self, index: usize, was_reached: bool) {
+        match (was_reached, self.is_reached(index)) {
+            (false, true) => self.reached += 1,
+            (true, false) => self.reached -= 1,
+            _ => {}
+        }
+    }
+
+    /// Cost paid by weighted solvers to enter `cell`.
+    pub fn cost(&self, cell: (usize, usize)) -> u32 {
+        self.costs.get(self.index(cell)).map_or(DEFAULT_COST, |&cost| cost as u32)
+    }
+
+    /// Cost of a path costing `cost` once it enters `cell`. Paths too costly to count
+    /// with a `u32` saturate just below `UNREACHED`, so that they still reach the cell.
+    pub fn cost_through(&self, cost: u32, cell: (usize, usize)) -> u32 {
+        cost.saturating_add(self.cost(cell)).min(UNREACHED - 1)
+    }
+
+    /// Sets the cost of `cell`.
+    ///
+    /// # Panics
+    ///
+    /// If `cost` is 0 or above `MAX_COST`.
+    pub fn set_cost(&mut self, cell: (usize, usize), cost: u32) {
+        assert!((1..=MAX_COST).contains(&cost), "The cost of a cell goes from 1 to {}, not {}", MAX_COST, cost);
+        if self.costs.is_empty() {
+            if cost == DEFAULT_COST {
+                return;
+            }
+            self.costs = vec![DEFAULT_COST as u8; self.width * self.height];
         }
-        if i + 1 < self.height {
-            neighbours.push((i + 1, j));
+        let index = self.index(cell);
+        self.costs[index] = cost as u8;
+    }
+
+    /// Cells next to `cell`, above, below, left and right in that order.
+    pub fn neighbours(&self, cell: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        NEIGHBOUR_ORDER
+            .into_iter()
+            .filter_map(move |direction| direction.offset(cell))
+            .filter(|&neighbour| self.contains(neighbour))
+    }
+
+    /// Cells next to `cell` that can be reached without crossing a wall, in the order of `neighbours`.
+    pub fn accessible_neighbours(&self, cell: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        NEIGHBOUR_ORDER.into_iter().filter_map(move |direction| direction.step_from(self, cell))
+    }
+
+    pub fn get_neighbours(&self, i: usize, j: usize) -> Vec<(usize, usize)> {
+        self.neighbours((i, j)).collect()
+    }
+
+    /// Returns the neighbours that can be reached from (i, j) without crossing a wall.
+    pub fn get_accessible_neighbours(&self, i: usize, j: usize) -> Vec<(usize, usize)> {
+        self.accessible_neighbours((i, j)).collect()
+    }
+
+    pub fn contains(&self, cell: (usize, usize)) -> bool {
+        cell.0 < self.height && cell.1 < self.width
+    }
+
+    /// Returns the cell reachable from `from` with the most moves, and that number of moves.
+    pub fn farthest_cell(&self, from: (usize, usize)) -> ((usize, usize), usize) {
+        let mut distances = vec![UNREACHED; self.width * self.height];
+        distances[self.index(from)] = 0;
+        let mut queue = VecDeque::from([from]);
+        let mut farthest = (from, 0);
+
+        while let Some(cell) = queue.pop_front() {
+            let distance = distances[self.index(cell)];
+            if distance > farthest.1 {
+                farthest = (cell, distance);
+            }
+
+            for neighbour in self.accessible_neighbours(cell) {
+                let index = self.index(neighbour);
+                if distances[index] == UNREACHED {
+                    distances[index] = distance + 1;
+                    queue.push_back(neighbour);
+                }
+            }
         }
-        if j > 0 {
-            neighbours.push((i, j - 1));
+
+        (farthest.0, farthest.1 as usize)
+    }
+
+    /// Number of cells on the shortest path between `from` and `to`, both included,
+    /// `None` if `to` can't be reached.
+    pub fn shortest_path_length(&self, from: (usize, usize), to: (usize, usize)) -> Option<usize> {
+        let mut distances = vec![UNREACHED; self.width * self.height];
+        distances[self.index(from)] = 1;
+        let mut queue = VecDeque::from([from]);
+
+        while let Some(cell) = queue.pop_front() {
+            let distance = distances[self.index(cell)];
+            if cell == to {
+                return Some(distance as usize);
+            }
+
+            for neighbour in self.accessible_neighbours(cell) {
+                let index = self.index(neighbour);
+                if distances[index] == UNREACHED {
+                    distances[index] = distance + 1;
+                    queue.push_back(neighbour);
+                }
+            }
         }
-        if j + 1 < self.width {
-            neighbours.push((i, j + 1));
+
+        None
+    }
+
+    /// Total cost of the cheapest path between `from` and `to`, counting the cost of every
+    /// cell entered after `from`, `None` if `to` can't be reached.
+    pub fn cheapest_path_cost(&self, from: (usize, usize), to: (usize, usize)) -> Option<usize> {
+        let mut costs = vec![usize::MAX; self.width * self.height];
+        costs[self.index(from)] = 0;
+        let mut queue = BinaryHeap::from([Reverse((0, from))]);
+
+        while let Some(Reverse((cost, cell))) = queue.pop() {
+            if cell == to {
+                return Some(cost);
+            }
+            if cost > costs[self.index(cell)] {
+                continue;
+            }
+
+            for neighbour in self.accessible_neighbours(cell) {
+                let index = self.index(neighbour);
+                let candidate = cost + self.cost(neighbour) as usize;
+                if candidate < costs[index] {
+                    costs[index] = candidate;
+                    queue.push(Reverse((candidate, neighbour)));
+                }
+            }
         }
 
-        neighbours
+        None
+    }
+
+    /// Moves the start and the exit to the two ends of the longest path of the maze.
+    /// The path is exact for perfect mazes and a good approximation for mazes with loops.
+    pub fn place_on_longest_path(&mut self) {
+        let (first_end, _) = self.farthest_cell(self.start);
+        let (second_end, _) = self.farthest_cell(first_end);
+
+        self.start = first_end;
+        self.exit = second_end;
+        self.current_cell = first_end;
     }
 
     pub fn get_non_visited_neighbours(&self, i: usize, j: usize) -> Vec<(usize, usize)> {
         let mut non_visited = Vec::new();
         let neighbours = self.get_neighbours(i, j);
         for (row, col) in neighbours {
-            if !self.cells[row][col].visited {
+            if !self.is_visited((row, col)) {
                 non_visited.push((row, col));
             }
         }
         non_visited
     }
 
-    pub fn generate_maze(width: usize, height: usize) -> Maze {
-        let mut maze = Maze::new(width, height);
+    /// Generates a perfect maze with the recursive backtracker.
+    pub fn generate_maze(width: usize, height: usize, seed: u64) -> Maze {
+        Maze::generate_with(&mut Backtracker::default(), width, height, seed)
+    }
 
-        maze.cells[0][0].visited = true;
+    pub fn generate_with(generator: &mut dyn Generator, width: usize, height: usize, seed: u64) -> Maze {
+        let mut rng = MazeRng::seed_from_u64(seed);
+        let mut maze = Maze::new(width, height);
+        maze.seed = Some(seed);
+        maze.generator = Some(generator.name());
+        generator.generate(&mut maze, &mut rng);
+        maze
+    }
 
-        let mut stack = Vec::new();
-        stack.push((0, 0));
-        maze.path.push((0, 0));
+    /// Generates a maze with extra passages, so that several routes exist,
+    /// and covers some regions with mud and water.
+    pub fn generate_weighted_with(generator: &mut dyn Generator, width: usize, height: usize, seed: u64) -> Maze {
+        let mut rng = MazeRng::seed_from_u64(seed);
+        let mut maze = Maze::new(width, height);
+        maze.seed = Some(seed);
+        maze.generator = Some(generator.name());
+        generator.generate(&mut maze, &mut rng);
+        maze.add_weights(&mut rng);
+        maze
+    }
 
-        while let Some(current_cell) = stack.last().copied() {
-            let non_visited_neighbours = maze.get_non_visited_neighbours(current_cell.0, current_cell.1);
+    /// Opens extra passages and paints terrain on a generated maze.
+    pub fn add_weights(&mut self, rng: &mut impl Rng) {
+        self.open_random_walls(self.width * self.height / 10, rng);
+        self.paint_terrain(rng);
+    }
 
-            if non_visited_neighbours.is_empty() {
-                stack.pop();
+    /// Removes up to `count` random interior walls, creating loops in the maze.
+    pub fn open_random_walls(&mut self, count: usize, rng: &mut impl Rng) {
+        for _ in 0..count {
+            let cell = (rng.random_range(0..self.height), rng.random_range(0..self.width));
+            let neighbours = self.get_neighbours(cell.0, cell.1);
+            let neighbour = neighbours[rng.random_range(0..neighbours.len())];
+            self.open_adjacent_wall(cell, neighbour);
+        }
+    }
 
-                if stack.is_empty() {
-                    break;
+    /// Paints round regions of mud and water, raising the cost of the cells they cover.
+    pub fn paint_terrain(&mut self, rng: &mut impl Rng) {
+        let regions = (self.width * self.height / 40).max(1);
+
+        for _ in 0..regions {
+            let center = (rng.random_range(0..self.height), rng.random_range(0..self.width));
+            let radius: usize = rng.random_range(1..=3);
+            let cost = if rng.random_bool(0.5) { MUD_COST } else { WATER_COST };
+
+            let rows = center.0.saturating_sub(radius)..(center.0 + radius + 1).min(self.height);
+            for row in rows {
+                let cols = center.1.saturating_sub(radius)..(center.1 + radius + 1).min(self.width);
+                for col in cols {
+                    let distance = row.abs_diff(center.0).pow(2) + col.abs_diff(center.1).pow(2);
+                    if distance <= radius * radius {
+                        self.set_cost((row, col), cost);
+                    }
                 }
-            } else {
-                let mut rng = rand::rng();
-                let random_number: usize = rng.random_range(0..non_visited_neighbours.len());
-                let selected_cell = non_visited_neighbours[random_number];
-
-                maze.cells[selected_cell.0][selected_cell.1].visited = true;
-
-                maze.open_adjacent_wall(current_cell, selected_cell);
-
-                stack.push(selected_cell);
-                maze.path.push(selected_cell);
             }
         }
-
-        maze
     }
 
     pub fn open_adjacent_wall(
@@ -97,34 +509,69 @@ impl Maze {
         first: (usize, usize),
         second: (usize, usize),
     ) {
-        if first == second {
-            return;
-        }
+        self.set_adjacent_wall(first, second, false);
+    }
 
-        let ((row1, col1), (row2, col2)) = (first, second);
+    pub fn close_adjacent_wall(
+        &mut self,
+        first: (usize, usize),
+        second: (usize, usize),
+    ) {
+        self.set_adjacent_wall(first, second, true);
+    }
 
-        if row1.abs_diff(row2) + col1.abs_diff(col2) != 1 {
-            return;
+    fn set_adjacent_wall(
+        &mut self,
+        first: (usize, usize),
+        second: (usize, usize),
+        wall: bool,
+    ) {
+        if let Some(direction) = Direction::between(first, second) {
+            self.set_wall(first, direction, wall);
         }
+    }
 
-        if row1 == row2 {
-            let (left, right) = if col1 < col2 { (first, second) } else { (second, first) };
-            self.cells[left.0][left.1].right_wall = false;
-            self.cells[right.0][right.1].left_wall = false;
-        } else {
-            let (top, bottom) = if row1 < row2 { (first, second) } else { (second, first) };
-            self.cells[top.0][top.1].bottom_wall = false;
-            self.cells[bottom.0][bottom.1].top_wall = false;
-        }
+    /// Records that the generator added `cell` to the maze.
+    pub fn mark_carved(&mut self, cell: (usize, usize)) {
+        self.last_carved = Some(cell);
+        self.carved += 1;
     }
 
+    /// Number of cells a solver went through or filled.
+    pub fn visited_count(&self) -> usize {
+        self.reached
+    }
+
+    /// Clears what the solvers left on the cells: the visited and filled flags.
     pub fn reset_visited_cells(&mut self) {
-        for row in &mut self.cells {
-            for cell in row {
-                cell.visited = false;
+        self.visited.clear();
+        self.filled.clear();
+        self.reached = 0;
+    }
+}
+
+/// Checks that rows of cells form a grid of at least one cell, and that both sides of
+/// every wall agree.
+pub fn check_walls(cells: &[Vec<Cell>]) -> Result<(), CellsError> {
+    let width = cells.first().map_or(0, Vec::len);
+    if width == 0 {
+        return Err(CellsError::Empty);
+    }
+    if let Some(row) = cells.iter().position(|row| row.len() != width) {
+        return Err(CellsError::UnevenRow(row));
+    }
+
+    for (i, row) in cells.iter().enumerate() {
+        for (j, cell) in row.iter().enumerate() {
+            if j + 1 < row.len() && cell.right_wall != row[j + 1].left_wall {
+                return Err(CellsError::Walls(WallMismatch { first: (i, j), second: (i, j + 1) }));
+            }
+            if i + 1 < cells.len() && cell.bottom_wall != cells[i + 1][j].top_wall {
+                return Err(CellsError::Walls(WallMismatch { first: (i, j), second: (i + 1, j) }));
             }
         }
     }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -135,20 +582,149 @@ mod tests {
     fn test_generate_maze() {
         let width = 10;
         let height = 10;
-        let maze = Maze::generate_maze(width, height);
+        let maze = Maze::generate_maze(width, height, 42);
 
         assert_eq!(maze.width, width);
         assert_eq!(maze.height, height);
-        assert_eq!(maze.cells.len(), height);
-        assert_eq!(maze.cells[0].len(), width);
+        assert_eq!(maze.cells().count(), width * height);
 
-        for row in &maze.cells {
-            for cell in row {
-                assert!(cell.visited, "All cells should be visited in a perfect maze.");
-            }
+        for (_, cell) in maze.cells() {
+            assert!(cell.visited, "All cells should be visited in a perfect maze.");
+        }
+
+        assert_eq!(maze.carved, width * height, "Every cell should be carved once.");
+    }
+
+    #[test]
+    fn test_generate_maze_is_reproducible() {
+        let first = Maze::generate_maze(15, 10, 7);
+        let second = Maze::generate_maze(15, 10, 7);
+        let other = Maze::generate_maze(15, 10, 8);
+
+        assert_eq!(first.seed, Some(7));
+        assert!(first.cells().eq(second.cells()), "The same seed should carve the same maze");
+        assert!(!first.cells().eq(other.cells()), "Another seed should carve another maze");
+    }
+
+    #[test]
+    fn test_generate_weighted_maze_is_reproducible() {
+        let first = Maze::generate_weighted_with(&mut Backtracker::default(), 15, 10, 7);
+        let second = Maze::generate_weighted_with(&mut Backtracker::default(), 15, 10, 7);
+
+        let walls = |maze: &Maze| -> Vec<(bool, bool, u32)> {
+            maze.cells().map(|(_, cell)| (cell.right_wall, cell.bottom_wall, cell.cost)).collect()
+        };
+        assert_eq!(walls(&first), walls(&second), "The same seed should give the same walls and terrain");
+    }
+
+    #[test]
+    fn test_generate_weighted_maze() {
+        let maze = Maze::generate_weighted_with(&mut Backtracker::default(), 20, 20, 42);
+
+        let costs: Vec<u32> = maze.cells().map(|(_, cell)| cell.cost).collect();
+        assert!(costs.iter().all(|cost| [1, MUD_COST, WATER_COST].contains(cost)), "Only known terrains should be painted");
+        assert!(costs.iter().any(|&cost| cost > 1), "At least one region should be painted");
+    }
+
+    #[test]
+    fn test_new_non_square_maze() {
+        let maze = Maze::new(7, 3);
+
+        assert_eq!(maze.start, (0, 0));
+        assert_eq!(maze.exit, (2, 6), "The exit should be the bottom-right cell, as (row, column)");
+        assert!(maze.contains(maze.exit));
+        assert!(!maze.contains((3, 0)));
+        assert!(!maze.contains((0, 7)));
+    }
+
+    #[test]
+    fn test_place_on_longest_path() {
+        let mut maze = Maze::new(5, 2);
+
+        // Corridor going right on the top row and back left on the bottom row
+        for col in 0..4 {
+            maze.open_adjacent_wall((0, col), (0, col + 1));
+            maze.open_adjacent_wall((1, col), (1, col + 1));
         }
+        maze.open_adjacent_wall((0, 4), (1, 4));
+        maze.start = (0, 2);
+
+        maze.place_on_longest_path();
+
+        let mut ends = [maze.start, maze.exit];
+        ends.sort();
+        assert_eq!(ends, [(0, 0), (1, 0)], "The two ends of the corridor should be used");
+        assert_eq!(maze.current_cell, maze.start);
+        assert_eq!(maze.farthest_cell(maze.start), (maze.exit, 9));
+    }
+
+    #[test]
+    fn test_shortest_path_length() {
+        let mut maze = Maze::new(3, 2);
+        maze.open_adjacent_wall((0, 0), (0, 1));
+        maze.open_adjacent_wall((0, 1), (1, 1));
+        maze.open_adjacent_wall((1, 1), (1, 2));
+
+        assert_eq!(maze.shortest_path_length((0, 0), (1, 2)), Some(4));
+        assert_eq!(maze.shortest_path_length((0, 0), (0, 0)), Some(1));
+        assert_eq!(maze.shortest_path_length((0, 0), (1, 0)), None);
+    }
+
+    #[test]
+    fn test_cheapest_path_cost() {
+        // Ring of four cells, the mud on one side makes the other side cheaper
+        let mut maze = Maze::new(2, 2);
+        maze.open_adjacent_wall((0, 0), (0, 1));
+        maze.open_adjacent_wall((0, 1), (1, 1));
+        maze.open_adjacent_wall((0, 0), (1, 0));
+        maze.open_adjacent_wall((1, 0), (1, 1));
+        maze.set_cost((0, 1), MUD_COST);
+
+        assert_eq!(maze.cheapest_path_cost((0, 0), (1, 1)), Some(2));
+        assert_eq!(maze.cheapest_path_cost((0, 0), (0, 1)), Some(MUD_COST as usize));
+        assert_eq!(maze.cheapest_path_cost((0, 0), (0, 0)), Some(0));
+
+        maze.close_adjacent_wall((1, 0), (1, 1));
+        assert_eq!(maze.cheapest_path_cost((0, 0), (1, 1)), Some(MUD_COST as usize + 1));
+    }
+
+    #[test]
+    fn test_cost_through_saturates() {
+        let mut maze = Maze::new(2, 1);
+        maze.set_cost((0, 1), MUD_COST);
+
+        assert_eq!(maze.cost_through(3, (0, 1)), 3 + MUD_COST);
+        assert_eq!(maze.cost_through(UNREACHED - 2, (0, 1)), UNREACHED - 1, "Costly paths should still reach the cell");
+    }
 
-        assert_eq!(maze.path.len(), width * height, "The path should cover the entire maze.");
+    #[test]
+    #[should_panic(expected = "goes from 1")]
+    fn test_set_cost_rejects_zero() {
+        Maze::new(2, 2).set_cost((0, 0), 0);
+    }
+
+    #[test]
+    fn test_visited_count() {
+        let mut maze = Maze::new(3, 3);
+        maze.set_visited((0, 0), true);
+        maze.set_visited((0, 0), true);
+        maze.set_filled((0, 0), true);
+        maze.set_filled((2, 1), true);
+        assert_eq!(maze.visited_count(), 2, "Cells both visited and filled count once");
+
+        maze.set_visited((0, 0), false);
+        assert_eq!(maze.visited_count(), 2);
+        maze.set_filled((0, 0), false);
+        assert_eq!(maze.visited_count(), 1);
+
+        maze.reset_visited_cells();
+        assert_eq!(maze.visited_count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one cell")]
+    fn test_new_rejects_empty_mazes() {
+        Maze::new(0, 3);
     }
 
     #[test]
@@ -181,6 +757,23 @@ mod tests {
         assert_eq!(neighbours, expected, "A bottom-edge cell should have 3 neighbors");
     }
 
+    #[test]
+    fn test_get_accessible_neighbours() {
+        let mut maze = Maze::new(5, 5);
+
+        // No wall opened yet, nothing is accessible
+        assert!(maze.get_accessible_neighbours(2, 2).is_empty(), "A closed cell should have no accessible neighbours");
+
+        maze.open_adjacent_wall((2, 2), (1, 2));
+        maze.open_adjacent_wall((2, 2), (2, 3));
+
+        let accessible = maze.get_accessible_neighbours(2, 2);
+        assert_eq!(accessible, vec![(1, 2), (2, 3)], "Only the opened passages should be accessible");
+
+        let accessible = maze.get_accessible_neighbours(2, 3);
+        assert_eq!(accessible, vec![(2, 2)], "Passages should be accessible from both sides");
+    }
+
     #[test]
     fn test_get_non_visited_neighbours() {
         let mut maze = Maze::new(5, 5);
@@ -191,15 +784,15 @@ mod tests {
         assert_eq!(non_visited, expected, "All neighbors should be unvisited at the start");
 
         // Mark (1,2) as visited
-        maze.cells[1][2].visited = true;
+        maze.set_visited((1, 2), true);
         let non_visited = maze.get_non_visited_neighbours(2, 2);
         let expected = vec![(3, 2), (2, 1), (2, 3)];
         assert_eq!(non_visited, expected, "Cell (1,2) should no longer be in the list");
 
         // Mark all neighbors as visited
-        maze.cells[3][2].visited = true;
-        maze.cells[2][1].visited = true;
-        maze.cells[2][3].visited = true;
+        maze.set_visited((3, 2), true);
+        maze.set_visited((2, 1), true);
+        maze.set_visited((2, 3), true);
         let non_visited = maze.get_non_visited_neighbours(2, 2);
         assert!(non_visited.is_empty(), "There should be no unvisited neighbors");
 
@@ -209,13 +802,13 @@ mod tests {
         assert_eq!(non_visited, expected, "The top-left corner should have 2 unvisited neighbors");
 
         // Mark (1,0) as visited
-        maze.cells[1][0].visited = true;
+        maze.set_visited((1, 0), true);
         let non_visited = maze.get_non_visited_neighbours(0, 0);
         let expected = vec![(0, 1)];
         assert_eq!(non_visited, expected, "Only (0,1) should be unvisited now");
 
         // Mark (0,1) as visited
-        maze.cells[0][1].visited = true;
+        maze.set_visited((0, 1), true);
         let non_visited = maze.get_non_visited_neighbours(0, 0);
         assert!(non_visited.is_empty(), "No unvisited neighbors should be left for (0,0)");
     }
@@ -228,8 +821,8 @@ mod tests {
         maze.open_adjacent_wall((2, 2), (2, 3));
 
         // Check walls
-        assert!(!maze.cells[2][2].right_wall, "Right wall of (2,2) should be open");
-        assert!(!maze.cells[2][3].left_wall, "Left wall of (2,3) should be open");
+        assert!(!maze.cell((2, 2)).right_wall, "Right wall of (2,2) should be open");
+        assert!(!maze.cell((2, 3)).left_wall, "Left wall of (2,3) should be open");
     }
 
     #[test]
@@ -240,8 +833,91 @@ mod tests {
         maze.open_adjacent_wall((2, 2), (3, 2));
 
         // Check walls
-        assert!(!maze.cells[2][2].bottom_wall, "Bottom wall of (2,2) should be open");
-        assert!(!maze.cells[3][2].top_wall, "Top wall of (3,2) should be open");
+        assert!(!maze.cell((2, 2)).bottom_wall, "Bottom wall of (2,2) should be open");
+        assert!(!maze.cell((3, 2)).top_wall, "Top wall of (3,2) should be open");
+    }
+
+    #[test]
+    fn test_close_adjacent_wall() {
+        let mut maze = Maze::new(5, 5);
+
+        maze.open_adjacent_wall((2, 2), (2, 3));
+        maze.close_adjacent_wall((2, 3), (2, 2));
+
+        assert!(maze.cell((2, 2)).right_wall, "Right wall of (2,2) should be closed again");
+        assert!(maze.cell((2, 3)).left_wall, "Left wall of (2,3) should be closed again");
+    }
+
+    #[test]
+    fn test_check_walls() {
+        let maze = Maze::generate_maze(6, 4, 3);
+        let mut cells: Vec<Vec<Cell>> =
+            (0..maze.height).map(|i| (0..maze.width).map(|j| maze.cell((i, j))).collect()).collect();
+        assert_eq!(check_walls(&cells), Ok(()));
+        assert!(Maze::from_cells(&cells).unwrap().cells().eq(maze.cells()));
+
+        cells[1][2].right_wall = !cells[1][2].right_wall;
+        assert_eq!(check_walls(&cells), Err(CellsError::Walls(WallMismatch { first: (1, 2), second: (1, 3) })));
+        assert!(Maze::from_cells(&cells).is_err());
+    }
+
+    #[test]
+    fn test_from_cells_rejects_bad_shapes_and_costs() {
+        let maze = Maze::new(3, 2);
+        let mut cells: Vec<Vec<Cell>> =
+            (0..maze.height).map(|i| (0..maze.width).map(|j| maze.cell((i, j))).collect()).collect();
+
+        assert_eq!(Maze::from_cells(&[]).unwrap_err(), CellsError::Empty);
+        assert_eq!(Maze::from_cells(&[Vec::new()]).unwrap_err(), CellsError::Empty);
+
+        cells[0][1].cost = MAX_COST + 1;
+        assert_eq!(Maze::from_cells(&cells).unwrap_err(), CellsError::CostTooHigh((0, 1), MAX_COST + 1));
+
+        cells[0][1].cost = 0;
+        assert_eq!(Maze::from_cells(&cells).unwrap_err(), CellsError::CostTooLow((0, 1)));
+
+        cells[1].pop();
+        assert_eq!(Maze::from_cells(&cells).unwrap_err(), CellsError::UnevenRow(1));
+    }
+
+    #[test]
+    fn test_walls_are_shared() {
+        let mut maze = Maze::new(3, 2);
+
+        maze.set_wall((0, 1), Direction::East, false);
+        assert!(!maze.cell((0, 2)).left_wall, "Both cells see the same wall");
+        maze.set_wall((1, 1), Direction::North, false);
+        assert!(!maze.cell((0, 1)).bottom_wall);
+        assert!(maze.cell((0, 0)).right_wall && maze.cell((1, 1)).bottom_wall);
+    }
+
+    #[test]
+    fn test_cell_flags_and_cost() {
+        let mut maze = Maze::new(70, 2);
+        maze.set_visited((1, 69), true);
+        maze.set_filled((0, 3), true);
+        maze.set_cost((1, 0), MUD_COST);
+
+        assert!(maze.is_visited((1, 69)) && !maze.is_visited((0, 69)));
+        assert!(maze.is_filled((0, 3)));
+        assert_eq!(maze.visited_count(), 2, "Filled cells count as visited");
+        assert_eq!((maze.cost((1, 0)), maze.cost((0, 0))), (MUD_COST, DEFAULT_COST));
+        assert_eq!(maze.cell((1, 0)), Cell { cost: MUD_COST, ..Cell::default() });
+
+        maze.reset_visited_cells();
+        assert_eq!(maze.visited_count(), 0);
+        assert!(!maze.is_filled((0, 3)));
+    }
+
+    #[test]
+    fn test_wall_count() {
+        let mut maze = Maze::new(3, 1);
+        maze.open_adjacent_wall((0, 0), (0, 1));
+        maze.open_adjacent_wall((0, 1), (0, 2));
+
+        assert_eq!(maze.cell((0, 0)).wall_count(), 3);
+        assert_eq!(maze.cell((0, 1)).wall_count(), 2);
+        assert_eq!(Maze::new(1, 1).cell((0, 0)).wall_count(), 4);
     }
 
     #[test]
@@ -252,8 +928,8 @@ mod tests {
         maze.open_adjacent_wall((2, 2), (4, 2));
 
         // Check that walls remain closed
-        assert!(maze.cells[2][2].bottom_wall, "Bottom wall of (2,2) should remain closed");
-        assert!(maze.cells[4][2].top_wall, "Top wall of (4,2) should remain closed");
+        assert!(maze.cell((2, 2)).bottom_wall, "Bottom wall of (2,2) should remain closed");
+        assert!(maze.cell((4, 2)).top_wall, "Top wall of (4,2) should remain closed");
     }
 
     #[test]
@@ -264,9 +940,9 @@ mod tests {
         maze.open_adjacent_wall((2, 2), (2, 2));
 
         // Ensure all walls remain closed
-        assert!(maze.cells[2][2].top_wall, "Top wall of (2,2) should remain closed");
-        assert!(maze.cells[2][2].bottom_wall, "Bottom wall of (2,2) should remain closed");
-        assert!(maze.cells[2][2].left_wall, "Left wall of (2,2) should remain closed");
-        assert!(maze.cells[2][2].right_wall, "Right wall of (2,2) should remain closed");
+        assert!(maze.cell((2, 2)).top_wall, "Top wall of (2,2) should remain closed");
+        assert!(maze.cell((2, 2)).bottom_wall, "Bottom wall of (2,2) should remain closed");
+        assert!(maze.cell((2, 2)).left_wall, "Left wall of (2,2) should remain closed");
+        assert!(maze.cell((2, 2)).right_wall, "Right wall of (2,2) should remain closed");
     }
 }
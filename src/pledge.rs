@@ -0,0 +1,263 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::direction::Direction;
+use crate::maze::Maze;
+use crate::solver::{SolveOutcome, Solver};
+use crate::wall_follower::Hand;
+
+/// Net turn made when going each way of `Hand::Right.preferences`, counted in quarter
+/// turns with right turns being positive.
+const TURNS: [i32; 4] = [1, 0, -1, -2];
+
+/// Pledge algorithm: walks straight in a preferred direction, and when a wall is in
+/// the way, follows it with the right hand until the sum of the turns made is back
+/// to zero. Unlike a plain wall follower, this gets it away from islands.
+///
+/// The turn counter can grow without bound when the walker is trapped with the
+/// exit out of reach, so those runs end with the step budget.
+#[derive(Clone, Debug)]
+pub struct Pledge {
+    pub maze: Rc<RefCell<Maze>>,
+    pub preferred_direction: Direction,
+    pub current_direction: Direction,
+    /// Sum of the turns made while following a wall, zero when walking straight.
+    pub turns: i32,
+    /// (cell, direction, turns) state the walker is compared with to find out it is looping.
+    /// It is moved forward after 1, 2, 4, 8... steps, as in Brent's cycle detection, so
+    /// that long runs don't need to remember every state.
+    checkpoint: State,
+    /// Steps since the checkpoint was moved, and after how many it moves again.
+    since_checkpoint: usize,
+    checkpoint_period: usize,
+    looping: bool,
+}
+
+type State = ((usize, usize), Direction, i32);
+
+impl Pledge {
+    pub fn new(maze: Rc<RefCell<Maze>>) -> Self {
+        let (start, preferred_direction) = {
+            let maze = maze.borrow();
+            (maze.start, preferred_direction(&maze))
+        };
+        Pledge {
+            maze,
+            preferred_direction,
+            current_direction: preferred_direction,
+            turns: 0,
+            checkpoint: (start, preferred_direction, 0),
+            since_checkpoint: 0,
+            checkpoint_period: 1,
+            looping: false,
+        }
+    }
+}
+
+/// Main direction from the start to the exit.
+fn preferred_direction(maze: &Maze) -> Direction {
+    let (start_row, start_column) = maze.start;
+    let (exit_row, exit_column) = maze.exit;
+
+    if exit_row.abs_diff(start_row) >= exit_column.abs_diff(start_column) {
+        if exit_row >= start_row { Direction::South } else { Direction::North }
+    } else if exit_column > start_column {
+        Direction::East
+    } else {
+        Direction::West
+    }
+}
+
+impl Solver for Pledge {
+    fn name(&self) -> &'static str {
+        "pledge"
+    }
+
+    fn label(&self) -> String {
+        format!("{} (heading {:?}, turns {})", self.name(), self.preferred_direction, self.turns)
+    }
+
+    fn maze(&self) -> &Rc<RefCell<Maze>> {
+        &self.maze
+    }
+
+    fn step(&mut self) {
+        let mut maze = self.maze.borrow_mut();
+        let cell = maze.current_cell;
+
+        let mut next = None;
+        if self.turns == 0 {
+            match self.preferred_direction.step_from(&maze, cell) {
+                Some(cell) => next = Some((cell, self.preferred_direction)),
+                None => {
+                    // Turn left to put the wall under the right hand
+                    self.current_direction = self.preferred_direction.turn_left();
+                    self.turns = -1;
+                }
+            }
+        }
+
+        if next.is_none() {
+            let preferences = Hand::Right.preferences(self.current_direction);
+            if let Some((index, (cell, direction))) = preferences
+                .into_iter()
+                .enumerate()
+                .find_map(|(index, direction)| direction.step_from(&maze, cell).map(|next| (index, (next, direction))))
+            {
+                self.turns += TURNS[index];
+                next = Some((cell, direction));
+            }
+        }
+
+        if let Some((cell, direction)) = next {
+            maze.current_cell = cell;
+            maze.set_visited(cell, true);
+            self.current_direction = direction;
+        }
+
+        let state = (maze.current_cell, self.current_direction, self.turns);
+        if state == self.checkpoint {
+            self.looping = true;
+        }
+        self.since_checkpoint += 1;
+        if self.since_checkpoint == self.checkpoint_period {
+            self.checkpoint = state;
+            self.since_checkpoint = 0;
+            self.checkpoint_period *= 2;
+        }
+    }
+
+    fn outcome(&self) -> Option<SolveOutcome> {
+        let maze = self.maze.borrow();
+        if maze.current_cell == maze.exit {
+            Some(SolveOutcome::Reached)
+        } else if self.looping {
+            Some(SolveOutcome::Unreachable)
+        } else {
+            None
+        }
+    }
+
+    fn reset(&mut self) {
+        let mut maze = self.maze.borrow_mut();
+        maze.reset_visited_cells();
+        let start = maze.start;
+        maze.current_cell = start;
+        maze.set_visited(start, true);
+        self.preferred_direction = preferred_direction(&maze);
+        self.current_direction = self.preferred_direction;
+        self.turns = 0;
+        self.checkpoint = (start, self.preferred_direction, 0);
+        self.since_checkpoint = 0;
+        self.checkpoint_period = 1;
+        self.looping = false;
+    }
+
+    fn direction(&self) -> Option<Direction> {
+        Some(self.current_direction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wall_follower::WallFollower;
+
+    /// 3x3 maze with a ring around the centre cell.
+    fn create_test_maze() -> Rc<RefCell<Maze>> {
+        let mut maze = Maze::new(3, 3);
+        maze.open_adjacent_wall((0, 0), (0, 1));
+        maze.open_adjacent_wall((0, 1), (0, 2));
+        maze.open_adjacent_wall((0, 2), (1, 2));
+        maze.open_adjacent_wall((1, 2), (2, 2));
+        maze.open_adjacent_wall((2, 2), (2, 1));
+        maze.open_adjacent_wall((2, 1), (2, 0));
+        maze.open_adjacent_wall((2, 0), (1, 0));
+        maze.open_adjacent_wall((1, 0), (0, 0));
+
+        Rc::new(RefCell::new(maze))
+    }
+
+    #[test]
+    fn test_preferred_direction() {
+        let maze = create_test_maze();
+        assert_eq!(Pledge::new(maze.clone()).preferred_direction, Direction::South);
+
+        maze.borrow_mut().exit = (0, 2);
+        assert_eq!(Pledge::new(maze.clone()).preferred_direction, Direction::East);
+    }
+
+    #[test]
+    fn test_pledge_walks_straight_first() {
+        let maze = create_test_maze();
+        let mut solver = Pledge::new(maze.clone());
+
+        solver.step();
+
+        assert_eq!(maze.borrow().current_cell, (1, 0));
+        assert_eq!(solver.turns, 0);
+    }
+
+    #[test]
+    fn test_pledge_execution() {
+        let maze = create_test_maze();
+        let mut solver = Pledge::new(maze.clone());
+
+        assert_eq!(solver.automatic_execution(), SolveOutcome::Reached);
+        assert_eq!(maze.borrow().current_cell, (2, 2));
+    }
+
+    #[test]
+    fn test_pledge_leaves_island() {
+        let mut maze = Maze::new(5, 4);
+        // Ring around the pillar at (2,2), with a branch going north from (1,2)
+        // towards the exit in the top-right corner
+        maze.open_adjacent_wall((1, 1), (1, 2));
+        maze.open_adjacent_wall((1, 2), (1, 3));
+        maze.open_adjacent_wall((1, 3), (2, 3));
+        maze.open_adjacent_wall((2, 3), (3, 3));
+        maze.open_adjacent_wall((3, 3), (3, 2));
+        maze.open_adjacent_wall((3, 2), (3, 1));
+        maze.open_adjacent_wall((3, 1), (2, 1));
+        maze.open_adjacent_wall((2, 1), (1, 1));
+        maze.open_adjacent_wall((1, 2), (0, 2));
+        maze.open_adjacent_wall((0, 2), (0, 3));
+        maze.open_adjacent_wall((0, 3), (0, 4));
+        maze.start = (1, 1);
+        maze.current_cell = (1, 1);
+        maze.exit = (0, 4);
+        let maze = Rc::new(RefCell::new(maze));
+
+        let mut right_hand = WallFollower::new(maze.clone(), Hand::Right);
+        right_hand.reset();
+        assert_eq!(right_hand.automatic_execution(), SolveOutcome::Unreachable, "The right hand should stay on the pillar");
+
+        let mut pledge = Pledge::new(maze.clone());
+        pledge.reset();
+        assert_eq!(pledge.automatic_execution(), SolveOutcome::Reached);
+    }
+
+    #[test]
+    fn test_pledge_trapped() {
+        let mut maze = Maze::new(2, 2);
+        maze.open_adjacent_wall((0, 0), (0, 1));
+        let maze = Rc::new(RefCell::new(maze));
+        let mut solver = Pledge::new(maze.clone());
+
+        assert_ne!(solver.automatic_execution(), SolveOutcome::Reached);
+        assert_ne!(maze.borrow().current_cell, (1, 1));
+    }
+
+    #[test]
+    fn test_pledge_reset() {
+        let maze = create_test_maze();
+        let mut solver = Pledge::new(maze.clone());
+
+        solver.automatic_execution();
+        solver.reset();
+
+        assert_eq!(maze.borrow().current_cell, (0, 0));
+        assert_eq!(solver.current_direction, Direction::South);
+        assert_eq!(solver.turns, 0);
+        assert!(!solver.is_finished());
+    }
+}
@@ -0,0 +1,206 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::maze::Maze;
+use crate::solver::{self, step_budget, SolveOutcome, Solver, SolverOptions};
+
+/// Fewest and most solvers that can take part in a race.
+pub const MIN_RACERS: usize = 2;
+pub const MAX_RACERS: usize = 4;
+
+/// A solver taking part in a race, working on its own copy of the maze.
+#[derive(Debug)]
+pub struct Racer {
+    pub solver: Box<dyn Solver>,
+    steps: usize,
+}
+
+impl Racer {
+    /// Number of steps taken so far.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Number of cells the solver went through or filled.
+    pub fn visited(&self) -> usize {
+        self.solver.maze().borrow().visited_count()
+    }
+
+    /// Number of cells of the path found, `None` until the solver knows it.
+    pub fn path_length(&self) -> Option<usize> {
+        let path = self.solver.path();
+        (!path.is_empty()).then_some(path.len())
+    }
+}
+
+/// Solvers running side by side on identical copies of a maze, stepped in lockstep.
+#[derive(Debug)]
+pub struct Race {
+    racers: Vec<Racer>,
+    budget: usize,
+}
+
+impl Race {
+    /// Creates a race between the solvers named `names`, `None` if a name is unknown
+    /// or if there are too few or too many of them.
+    pub fn new(names: &[&str], maze: &Maze, options: &SolverOptions) -> Option<Race> {
+        if !(MIN_RACERS..=MAX_RACERS).contains(&names.len()) {
+            return None;
+        }
+
+        let mut racers = Vec::new();
+        for name in names {
+            let mut solver = solver::create(name, Rc::new(RefCell::new(maze.clone())), options)?;
+            solver.reset();
+            racers.push(Racer { solver, steps: 0 });
+        }
+
+        Some(Race { racers, budget: step_budget(maze) })
+    }
+
+    pub fn racers(&self) -> &[Racer] {
+        &self.racers
+    }
+
+    /// Names of the solvers taking part, in order.
+    pub fn names(&self) -> Vec<&'static str> {
+        self.racers.iter().map(|racer| racer.solver.name()).collect()
+    }
+
+    /// How the run of the racer at `index` ended, counting the step budget.
+    pub fn outcome(&self, index: usize) -> Option<SolveOutcome> {
+        let racer = &self.racers[index];
+        racer.solver.outcome().or((racer.steps >= self.budget).then_some(SolveOutcome::BudgetExceeded))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        (0..self.racers.len()).all(|index| self.outcome(index).is_some())
+    }
+
+    /// Steps every racer that is still running once.
+    pub fn step(&mut self) {
+        for index in 0..self.racers.len() {
+            if self.outcome(index).is_none() {
+                let racer = &mut self.racers[index];
+                racer.solver.step();
+                racer.steps += 1;
+            }
+        }
+    }
+
+    /// Runs the race until every racer is done.
+    pub fn end(&mut self) {
+        while !self.is_finished() {
+            self.step();
+        }
+    }
+
+    /// Puts every racer back at the start.
+    pub fn reset(&mut self) {
+        for racer in &mut self.racers {
+            racer.solver.reset();
+            racer.steps = 0;
+        }
+    }
+
+    /// Racers that reached the exit in the fewest steps, empty while nobody did.
+    pub fn winners(&self) -> Vec<usize> {
+        let reached: Vec<usize> = (0..self.racers.len())
+            .filter(|&index| self.outcome(index) == Some(SolveOutcome::Reached))
+            .collect();
+        let Some(fewest) = reached.iter().map(|&index| self.racers[index].steps).min() else {
+            return Vec::new();
+        };
+
+        reached.into_iter().filter(|&index| self.racers[index].steps == fewest).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 3x3 maze with a dead end that the right hand explores before reaching the exit.
+    fn create_test_maze() -> Maze {
+        let mut maze = Maze::new(3, 3);
+        maze.open_adjacent_wall((0, 0), (0, 1));
+        maze.open_adjacent_wall((0, 1), (0, 2));
+        maze.open_adjacent_wall((0, 2), (1, 2));
+        maze.open_adjacent_wall((1, 2), (1, 1));
+        maze.open_adjacent_wall((0, 0), (1, 0));
+        maze.open_adjacent_wall((1, 0), (2, 0));
+        maze.open_adjacent_wall((2, 0), (2, 1));
+        maze.open_adjacent_wall((2, 1), (2, 2));
+        maze
+    }
+
+    #[test]
+    fn test_race_requires_two_to_four_solvers() {
+        let maze = create_test_maze();
+        let options = SolverOptions::default();
+
+        assert!(Race::new(&["bfs"], &maze, &options).is_none());
+        assert!(Race::new(&["bfs", "astar", "dijkstra", "pledge", "tremaux"], &maze, &options).is_none());
+        assert!(Race::new(&["bfs", "unknown"], &maze, &options).is_none());
+        assert!(Race::new(&["bfs", "bfs"], &maze, &options).is_some());
+    }
+
+    #[test]
+    fn test_racers_use_their_own_maze() {
+        let maze = create_test_maze();
+        let mut race = Race::new(&["right-hand", "bfs"], &maze, &SolverOptions::default()).unwrap();
+
+        race.step();
+
+        let right_hand = race.racers()[0].solver.maze();
+        let bfs = race.racers()[1].solver.maze();
+        assert!(!Rc::ptr_eq(right_hand, bfs));
+        assert_eq!(right_hand.borrow().current_cell, (0, 1));
+        assert_eq!(bfs.borrow().current_cell, (0, 0));
+        assert!(!maze.cell((0, 1)).visited, "The original maze should be left untouched");
+    }
+
+    #[test]
+    fn test_race_lockstep_and_winner() {
+        let maze = create_test_maze();
+        let mut race = Race::new(&["right-hand", "bfs"], &maze, &SolverOptions::default()).unwrap();
+
+        race.step();
+        race.step();
+        assert_eq!(race.racers()[0].steps(), 2);
+        assert_eq!(race.racers()[1].steps(), 2);
+        assert!(race.winners().is_empty());
+
+        race.end();
+
+        assert!(race.is_finished());
+        assert_eq!(race.winners(), vec![1], "The right hand walks through the dead end");
+        assert!(race.racers()[0].steps() > race.racers()[1].steps());
+        assert_eq!(race.racers()[0].visited(), 9);
+    }
+
+    #[test]
+    fn test_race_path_length() {
+        let maze = create_test_maze();
+        let mut race = Race::new(&["bfs", "dijkstra"], &maze, &SolverOptions::default()).unwrap();
+
+        assert_eq!(race.racers()[0].path_length(), None);
+        race.end();
+
+        assert_eq!(race.racers()[0].path_length(), Some(5));
+        assert_eq!(race.racers()[1].path_length(), Some(5));
+    }
+
+    #[test]
+    fn test_race_reset() {
+        let maze = create_test_maze();
+        let mut race = Race::new(&["right-hand", "bfs"], &maze, &SolverOptions::default()).unwrap();
+
+        race.end();
+        race.reset();
+
+        assert!(!race.is_finished());
+        assert_eq!(race.racers()[0].steps(), 0);
+        assert_eq!(race.racers()[0].solver.current_cell(), (0, 0));
+        assert!(race.winners().is_empty());
+    }
+}
@@ -0,0 +1,218 @@
+//! Headless recording of solver runs as a sequence of pictures, encoded as an animated GIF
+//! with the `gif` feature.
+
+use crate::render::{rasterize, Image, RenderOptions, Scene};
+use crate::solver::Solver;
+
+/// Pictures of a solver run, from the start until the solver is finished or ran out of steps.
+///
+/// The first picture shows the solver at the start and the last one how the run ended,
+/// with one picture every `every` steps in between.
+pub struct Frames<'a> {
+    solver: &'a mut dyn Solver,
+    options: RenderOptions,
+    every: usize,
+    budget: usize,
+    steps: usize,
+    started: bool,
+    done: bool,
+}
+
+/// Records the run of `solver` within `budget` steps, drawing a picture every `every` steps.
+pub fn frames(solver: &mut dyn Solver, options: RenderOptions, every: usize, budget: usize) -> Frames<'_> {
+    solver.reset();
+    Frames { solver, options, every: every.max(1), budget, steps: 0, started: false, done: false }
+}
+
+impl Frames<'_> {
+    fn picture(&self) -> Image {
+        let maze = self.solver.maze().borrow();
+        rasterize(&Scene::new(&maze).solver(&*self.solver), &self.options)
+    }
+
+    fn is_over(&self) -> bool {
+        self.solver.is_finished() || self.steps >= self.budget
+    }
+}
+
+impl Iterator for Frames<'_> {
+    type Item = Image;
+
+    fn next(&mut self) -> Option<Image> {
+        if !self.started {
+            self.started = true;
+            self.done = self.is_over();
+            return Some(self.picture());
+        }
+        if self.done {
+            return None;
+        }
+
+        for _ in 0..self.every {
+            if self.is_over() {
+                break;
+            }
+            self.solver.step();
+            self.steps += 1;
+        }
+        self.done = self.is_over();
+        Some(self.picture())
+    }
+}
+
+/// How long the last picture of an animation stays on screen before it loops, in milliseconds.
+#[cfg(feature = "gif")]
+const LAST_FRAME_DELAY: u32 = 2000;
+
+/// Encodes `frames` as a looping GIF, showing each picture for `delay` milliseconds.
+///
+/// Pictures are expected to only use the colours of `palette`; any other colour is
+/// replaced with the closest one.
+#[cfg(feature = "gif")]
+pub fn write_gif(
+    frames: impl Iterator<Item = Image>,
+    palette: &[crate::render::Rgb],
+    delay: u32,
+    out: impl std::io::Write,
+) -> Result<(), gif::EncodingError> {
+    use std::borrow::Cow;
+    use std::collections::HashMap;
+
+    let mut frames = frames.peekable();
+    let Some(first) = frames.peek() else {
+        return Ok(());
+    };
+    let (width, height) = match (u16::try_from(first.width), u16::try_from(first.height)) {
+        (Ok(width), Ok(height)) => (width, height),
+        _ => return Err(std::io::Error::other("the pictures are too large for a GIF").into()),
+    };
+
+    let mut encoder = gif::Encoder::new(out, width, height, &palette.concat())?;
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+
+    let mut indices = HashMap::new();
+    while let Some(image) = frames.next() {
+        let buffer = image
+            .pixels
+            .chunks_exact(3)
+            .map(|pixel| {
+                let color = [pixel[0], pixel[1], pixel[2]];
+                *indices.entry(color).or_insert_with(|| closest_color(palette, color))
+            })
+            .collect();
+        let delay = if frames.peek().is_some() { delay } else { delay.max(LAST_FRAME_DELAY) };
+
+        encoder.write_frame(&gif::Frame {
+            width,
+            height,
+            // GIF delays are counted in hundredths of a second
+            delay: (delay / 10).min(u16::MAX as u32) as u16,
+            buffer: Cow::Owned(buffer),
+            ..gif::Frame::default()
+        })?;
+    }
+    Ok(())
+}
+
+/// Index of the colour of `palette` closest to `color`.
+#[cfg(feature = "gif")]
+fn closest_color(palette: &[crate::render::Rgb], color: crate::render::Rgb) -> u8 {
+    let distance = |other: &crate::render::Rgb| {
+        (0..3).map(|index| (other[index] as i32 - color[index] as i32).pow(2)).sum::<i32>()
+    };
+    (0..palette.len()).min_by_key(|&index| distance(&palette[index])).unwrap_or(0) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use super::*;
+    use crate::maze::Maze;
+    use crate::solver::{self, SolverOptions};
+
+    /// 3x2 maze with a single corridor going right, down and back left.
+    fn create_test_maze() -> Maze {
+        let mut maze = Maze::new(3, 2);
+        maze.open_adjacent_wall((0, 0), (0, 1));
+        maze.open_adjacent_wall((0, 1), (0, 2));
+        maze.open_adjacent_wall((0, 2), (1, 2));
+        maze.open_adjacent_wall((1, 2), (1, 1));
+        maze.open_adjacent_wall((1, 1), (1, 0));
+        maze.exit = (1, 0);
+        maze
+    }
+
+    fn create_test_options() -> RenderOptions {
+        RenderOptions { cell_size: 10, wall_thickness: 2, ..RenderOptions::terminal() }
+    }
+
+    #[test]
+    fn test_frames_every_step() {
+        let maze = Rc::new(RefCell::new(create_test_maze()));
+        let mut solver = solver::create("right-hand", maze, &SolverOptions::default()).unwrap();
+
+        let pictures: Vec<Image> = frames(solver.as_mut(), create_test_options(), 1, 100).collect();
+
+        assert_eq!(pictures.len(), 6, "The start and one picture for each of the 5 steps");
+        assert_eq!(pictures[0].pixel(6, 6), create_test_options().current);
+        assert_ne!(pictures[0], pictures[1]);
+        assert!(solver.is_finished());
+    }
+
+    #[test]
+    fn test_frames_every_k_steps() {
+        let maze = Rc::new(RefCell::new(create_test_maze()));
+        let mut solver = solver::create("right-hand", maze, &SolverOptions::default()).unwrap();
+
+        let count = frames(solver.as_mut(), create_test_options(), 2, 100).count();
+
+        assert_eq!(count, 4, "Pictures after steps 0, 2, 4 and 5");
+    }
+
+    #[test]
+    fn test_frames_budget() {
+        let maze = Rc::new(RefCell::new(create_test_maze()));
+        let mut solver = solver::create("right-hand", maze, &SolverOptions::default()).unwrap();
+
+        let count = frames(solver.as_mut(), create_test_options(), 1, 3).count();
+
+        assert_eq!(count, 4);
+        assert!(!solver.is_finished());
+    }
+
+    #[cfg(feature = "gif")]
+    #[test]
+    fn test_write_gif() {
+        let maze = Rc::new(RefCell::new(create_test_maze()));
+        let mut solver = solver::create("bfs", maze, &SolverOptions::default()).unwrap();
+        let options = create_test_options();
+        let mut out = Vec::new();
+
+        write_gif(frames(solver.as_mut(), options, 1, 100), &options.palette(), 50, &mut out).unwrap();
+
+        let mut decoder = gif::DecodeOptions::new();
+        decoder.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = decoder.read_info(out.as_slice()).unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (32, 22));
+        let mut delays = Vec::new();
+        let mut last = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            delays.push(frame.delay);
+            last = frame.buffer.to_vec();
+        }
+        assert!(delays.len() > 2);
+        assert_eq!(delays[0], 5);
+        assert_eq!(*delays.last().unwrap(), 200, "The last picture is held longer");
+        assert_eq!(&last[..4], &[229, 229, 229, 255], "The top-left corner is a wall");
+    }
+
+    #[cfg(feature = "gif")]
+    #[test]
+    fn test_closest_color() {
+        let palette = [[0, 0, 0], [255, 255, 255], [205, 0, 0]];
+
+        assert_eq!(closest_color(&palette, [250, 240, 255]), 1);
+        assert_eq!(closest_color(&palette, [180, 20, 10]), 2);
+    }
+}
@@ -0,0 +1,521 @@
+//! Rendering of mazes to SVG and to raster images, for output that doesn't fit in a terminal.
+//!
+//! A [`Scene`] is drawn as a list of axis-aligned rectangles, written as SVG `<rect>` elements
+//! or filled pixel by pixel into an [`Image`], which can be encoded as PNG with the `png` feature.
+
+use std::fmt::Write as _;
+use crate::cell::{MUD_COST, WATER_COST};
+use crate::maze::Maze;
+use crate::solver::Solver;
+
+/// Largest number of pixels of a picture, about 300 MB once rasterized.
+pub const MAX_PIXELS: u64 = 100_000_000;
+
+/// Colour as red, green and blue components.
+pub type Rgb = [u8; 3];
+
+/// Parses a colour written as `#rrggbb`, the `#` being optional.
+pub fn parse_color(value: &str) -> Option<Rgb> {
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let component = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).ok();
+    Some([component(0)?, component(2)?, component(4)?])
+}
+
+fn hex(color: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+/// Sizes in pixels and colours of a rendered maze.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RenderOptions {
+    /// Width and height of a cell, walls included.
+    pub cell_size: u32,
+    pub wall_thickness: u32,
+    pub background: Rgb,
+    pub wall: Rgb,
+    pub path: Rgb,
+    /// Colour of the most visited cells, the others fading towards the background.
+    pub heat: Rgb,
+    pub start: Rgb,
+    pub exit: Rgb,
+    pub mud: Rgb,
+    pub water: Rgb,
+    /// Colour of the cells flagged as visited by a solver.
+    pub visited: Rgb,
+    /// Colour of the cells filled by the filling solvers.
+    pub filled: Rgb,
+    /// Colour of the cells waiting to be explored by the solver.
+    pub frontier: Rgb,
+    /// Colour of the cell the solver is on.
+    pub current: Rgb,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            cell_size: 20,
+            wall_thickness: 2,
+            background: [255, 255, 255],
+            wall: [0, 0, 0],
+            path: [50, 160, 70],
+            heat: [230, 80, 40],
+            start: [0, 170, 0],
+            exit: [210, 0, 0],
+            mud: [139, 90, 43],
+            water: [70, 130, 220],
+            visited: [220, 220, 220],
+            filled: [245, 175, 175],
+            frontier: [150, 215, 235],
+            current: [230, 180, 0],
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Colours of the terminal canvas of the TUI: white walls on black, dark gray visited
+    /// cells, a yellow solver, a green start and a red exit.
+    pub fn terminal() -> Self {
+        RenderOptions {
+            background: [0, 0, 0],
+            wall: [229, 229, 229],
+            path: [85, 255, 85],
+            heat: [205, 0, 0],
+            start: [0, 205, 0],
+            exit: [205, 0, 0],
+            mud: [139, 90, 43],
+            water: [0, 0, 238],
+            visited: [127, 127, 127],
+            filled: [205, 0, 0],
+            frontier: [0, 205, 205],
+            current: [205, 205, 0],
+            ..RenderOptions::default()
+        }
+    }
+
+    /// Every colour a picture can use, apart from the shades of the heatmap.
+    pub fn palette(&self) -> Vec<Rgb> {
+        let mut palette = Vec::new();
+        for color in [
+            self.background,
+            self.wall,
+            self.path,
+            self.heat,
+            self.start,
+            self.exit,
+            self.mud,
+            self.water,
+            self.visited,
+            self.filled,
+            self.frontier,
+            self.current,
+        ] {
+            if !palette.contains(&color) {
+                palette.push(color);
+            }
+        }
+        palette
+    }
+}
+
+/// A maze with what is drawn over it.
+#[derive(Clone, Debug)]
+pub struct Scene<'a> {
+    maze: &'a Maze,
+    path: Vec<(usize, usize)>,
+    heat: Option<Vec<Vec<usize>>>,
+    frontier: Vec<(usize, usize)>,
+    current: Option<(usize, usize)>,
+}
+
+impl<'a> Scene<'a> {
+    /// Scene of `maze` alone, along with the cells solvers flagged as visited or filled.
+    pub fn new(maze: &'a Maze) -> Self {
+        Scene { maze, path: Vec::new(), heat: None, frontier: Vec::new(), current: None }
+    }
+
+    /// Draws where `solver` is, its frontier and the path it found, like the TUI does.
+    pub fn solver(mut self, solver: &dyn Solver) -> Self {
+        self.frontier = solver.frontier();
+        self.path = solver.path();
+        self.current = Some(solver.current_cell());
+        self
+    }
+
+    /// Draws `path` as a line going through the centres of its cells.
+    pub fn path(mut self, path: Vec<(usize, usize)>) -> Self {
+        self.path = path;
+        self
+    }
+
+    /// Shades every cell by the number of times it was visited, as given by [`visit_counts`].
+    pub fn heat(mut self, counts: Vec<Vec<usize>>) -> Self {
+        self.heat = Some(counts);
+        self
+    }
+}
+
+/// Runs `solver` from the start for at most `budget` steps, counting how many times
+/// each cell was visited.
+pub fn visit_counts(solver: &mut dyn Solver, budget: usize) -> Vec<Vec<usize>> {
+    solver.reset();
+    let (width, height) = {
+        let maze = solver.maze().borrow();
+        (maze.width, maze.height)
+    };
+    let mut counts = vec![vec![0; width]; height];
+    let start = solver.current_cell();
+    counts[start.0][start.1] += 1;
+
+    for _ in 0..budget {
+        if solver.is_finished() {
+            break;
+        }
+        solver.step();
+        let cell = solver.current_cell();
+        counts[cell.0][cell.1] += 1;
+    }
+    counts
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Rect {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    color: Rgb,
+}
+
+/// Mixes `from` with `amount` of `to`, `amount` going from 0 to 1.
+fn blend(from: Rgb, to: Rgb, amount: f64) -> Rgb {
+    let mix = |index: usize| (from[index] as f64 + (to[index] as f64 - from[index] as f64) * amount).round() as u8;
+    [mix(0), mix(1), mix(2)]
+}
+
+/// Width and height in pixels of the picture of `maze`, `None` when it has more than `MAX_PIXELS` pixels.
+pub fn picture_size(maze: &Maze, options: &RenderOptions) -> Option<(u32, u32)> {
+    let side = |cells: usize| u32::try_from(cells).ok()?.checked_mul(options.cell_size)?.checked_add(options.wall_thickness);
+    let (width, height) = (side(maze.width)?, side(maze.height)?);
+    (width as u64 * height as u64 <= MAX_PIXELS).then_some((width, height))
+}
+
+/// Rectangles making up the picture of `scene`, from the bottom one to the top one.
+fn shapes(scene: &Scene, options: &RenderOptions) -> Vec<Rect> {
+    let maze = scene.maze;
+    let size = options.cell_size;
+    let thickness = options.wall_thickness;
+    let (width, height) = picture_size(maze, options).expect("The picture should have been checked with picture_size");
+    let origin = |(row, col): (usize, usize)| (col as u32 * size + thickness, row as u32 * size + thickness);
+    let inner = size.saturating_sub(thickness);
+    let ratio = |ratio: f64| ((inner as f64 * ratio).round() as u32).max(1);
+    // Square of `side` pixels in the middle of a cell
+    let centered = |cell: (usize, usize), side: u32, color: Rgb| {
+        let (x, y) = origin(cell);
+        let offset = (inner - side.min(inner)) / 2;
+        Rect { x: x + offset, y: y + offset, width: side, height: side, color }
+    };
+
+    let mut shapes = vec![Rect { x: 0, y: 0, width, height, color: options.background }];
+
+    let max_visits = scene.heat.iter().flatten().flatten().copied().max().unwrap_or(0);
+    for ((i, j), cell) in maze.cells() {
+        if cell.visited || cell.filled {
+            let (x, y) = origin((i, j));
+            let color = if cell.filled { options.filled } else { options.visited };
+            shapes.push(Rect { x, y, width: inner, height: inner, color });
+        }
+
+        let visits = scene.heat.as_ref().map_or(0, |counts| counts[i][j]);
+        if visits > 0 {
+            let amount = 0.25 + 0.75 * visits as f64 / max_visits as f64;
+            let (x, y) = origin((i, j));
+            let color = blend(options.background, options.heat, amount);
+            shapes.push(Rect { x, y, width: inner, height: inner, color });
+        }
+
+        if cell.cost >= WATER_COST {
+            shapes.push(centered((i, j), ratio(0.5), options.water));
+        } else if cell.cost >= MUD_COST {
+            shapes.push(centered((i, j), ratio(0.5), options.mud));
+        }
+    }
+
+    for &cell in &scene.frontier {
+        let (x, y) = origin(cell);
+        shapes.push(Rect { x, y, width: inner, height: inner, color: options.frontier });
+    }
+
+    let line = ratio(0.25);
+    for (index, &cell) in scene.path.iter().enumerate() {
+        let mut square = centered(cell, line, options.path);
+        if let Some(&next) = scene.path.get(index + 1) {
+            // Stretch the square to the centre of the next cell
+            let (x, y) = (square.x as i64, square.y as i64);
+            let (dx, dy) = (
+                (next.1 as i64 - cell.1 as i64) * size as i64,
+                (next.0 as i64 - cell.0 as i64) * size as i64,
+            );
+            square.x = x.min(x + dx) as u32;
+            square.y = y.min(y + dy) as u32;
+            square.width = line + dx.unsigned_abs() as u32;
+            square.height = line + dy.unsigned_abs() as u32;
+        }
+        shapes.push(square);
+    }
+
+    shapes.push(centered(maze.start, ratio(0.6), options.start));
+    shapes.push(centered(maze.exit, ratio(0.6), options.exit));
+    if let Some(current) = scene.current {
+        shapes.push(centered(current, ratio(0.4), options.current));
+    }
+
+    for ((i, j), cell) in maze.cells() {
+        let (x, y) = (j as u32 * size, i as u32 * size);
+        let wall = |x, y, width, height| Rect { x, y, width, height, color: options.wall };
+        if cell.top_wall {
+            shapes.push(wall(x, y, size + thickness, thickness));
+        }
+        if cell.left_wall {
+            shapes.push(wall(x, y, thickness, size + thickness));
+        }
+        if cell.bottom_wall && i + 1 == maze.height {
+            shapes.push(wall(x, y + size, size + thickness, thickness));
+        }
+        if cell.right_wall && j + 1 == maze.width {
+            shapes.push(wall(x + size, y, thickness, size + thickness));
+        }
+    }
+
+    shapes
+}
+
+/// Draws `scene` as an SVG document.
+///
+/// # Panics
+///
+/// If the picture is too large, which `picture_size` tells beforehand.
+pub fn to_svg(scene: &Scene, options: &RenderOptions) -> String {
+    let (width, height) = picture_size(scene.maze, options).expect("The picture should have been checked with picture_size");
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\" \
+         shape-rendering=\"crispEdges\">\n",
+        width, height,
+    );
+    for rect in shapes(scene, options) {
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+            rect.x, rect.y, rect.width, rect.height, hex(rect.color),
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Picture stored as rows of RGB pixels.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn pixel(&self, x: u32, y: u32) -> Rgb {
+        let index = 3 * (y as usize * self.width as usize + x as usize);
+        [self.pixels[index], self.pixels[index + 1], self.pixels[index + 2]]
+    }
+
+    fn fill(&mut self, rect: &Rect) {
+        let right = (rect.x + rect.width).min(self.width);
+        let bottom = (rect.y + rect.height).min(self.height);
+        for y in rect.y.min(bottom)..bottom {
+            let row = 3 * y as usize * self.width as usize;
+            for x in rect.x.min(right)..right {
+                let index = row + 3 * x as usize;
+                self.pixels[index..index + 3].copy_from_slice(&rect.color);
+            }
+        }
+    }
+
+    /// Encodes the image as an 8-bit RGB PNG.
+    #[cfg(feature = "png")]
+    pub fn write_png(&self, out: impl std::io::Write) -> Result<(), png::EncodingError> {
+        let mut encoder = png::Encoder::new(out, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+        writer.finish()
+    }
+}
+
+/// Draws `scene` into an image.
+///
+/// # Panics
+///
+/// If the picture is too large, which `picture_size` tells beforehand.
+pub fn rasterize(scene: &Scene, options: &RenderOptions) -> Image {
+    let (width, height) = picture_size(scene.maze, options).expect("The picture should have been checked with picture_size");
+    let mut image = Image { width, height, pixels: vec![0; 3 * width as usize * height as usize] };
+    for rect in shapes(scene, options) {
+        image.fill(&rect);
+    }
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use super::*;
+    use crate::solver::{self, SolverOptions};
+
+    /// 3x2 maze with a single corridor going right, down and back left.
+    fn create_test_maze() -> Maze {
+        let mut maze = Maze::new(3, 2);
+        maze.open_adjacent_wall((0, 0), (0, 1));
+        maze.open_adjacent_wall((0, 1), (0, 2));
+        maze.open_adjacent_wall((0, 2), (1, 2));
+        maze.open_adjacent_wall((1, 2), (1, 1));
+        maze.open_adjacent_wall((1, 1), (1, 0));
+        maze.exit = (1, 0);
+        maze
+    }
+
+    fn create_test_options() -> RenderOptions {
+        RenderOptions { cell_size: 10, wall_thickness: 2, ..RenderOptions::default() }
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("#ff8000"), Some([255, 128, 0]));
+        assert_eq!(parse_color("0a0B0c"), Some([10, 11, 12]));
+        assert_eq!(parse_color("#fff"), None);
+        assert_eq!(parse_color("#gg0000"), None);
+        assert_eq!(hex([255, 128, 0]), "#ff8000");
+    }
+
+    #[test]
+    fn test_rasterize_walls() {
+        let maze = create_test_maze();
+        let options = create_test_options();
+
+        let image = rasterize(&Scene::new(&maze), &options);
+
+        assert_eq!((image.width, image.height), (32, 22));
+        assert_eq!(image.pixels.len(), 32 * 22 * 3);
+        assert_eq!(image.pixel(0, 0), options.wall);
+        assert_eq!(image.pixel(31, 21), options.wall);
+        assert_eq!(image.pixel(11, 5), options.background, "The wall between (0, 0) and (0, 1) is open");
+        assert_eq!(image.pixel(5, 11), options.wall, "The wall between (0, 0) and (1, 0) is closed");
+        assert_eq!(image.pixel(7, 7), options.start);
+        assert_eq!(image.pixel(7, 17), options.exit);
+    }
+
+    #[test]
+    fn test_rasterize_path_and_heat() {
+        let maze = create_test_maze();
+        let options = create_test_options();
+        let mut counts = vec![vec![0; 3]; 2];
+        counts[0][1] = 2;
+        counts[0][2] = 1;
+
+        let scene = Scene::new(&maze).path(vec![(0, 0), (0, 1), (0, 2)]).heat(counts);
+        let image = rasterize(&scene, &options);
+
+        assert_eq!(image.pixel(16, 5), options.path, "The path goes through the centre of (0, 1)");
+        assert_eq!(image.pixel(11, 6), options.path, "The path joins the centres of its cells");
+        assert_eq!(image.pixel(16, 8), options.heat);
+        assert_eq!(image.pixel(13, 3), options.heat, "The most visited cell gets the full heat colour");
+        assert_ne!(image.pixel(23, 3), options.heat);
+        assert_ne!(image.pixel(23, 3), options.background);
+        assert_eq!(image.pixel(23, 13), options.background, "Cells never visited aren't shaded");
+    }
+
+    #[test]
+    fn test_rasterize_solver() {
+        let maze = Rc::new(RefCell::new(create_test_maze()));
+        let mut solver = solver::create("bfs", maze.clone(), &SolverOptions::default()).unwrap();
+        solver.step();
+        solver.step();
+        let options = RenderOptions { cell_size: 10, wall_thickness: 2, ..RenderOptions::terminal() };
+
+        let maze = maze.borrow();
+        let image = rasterize(&Scene::new(&maze).solver(solver.as_ref()), &options);
+
+        let (row, col) = solver.current_cell();
+        let (x, y) = (col as u32 * 10 + 2, row as u32 * 10 + 2);
+        assert_eq!(image.pixel(x + 4, y + 4), options.current);
+        assert_eq!(image.pixel(x, y), options.visited);
+        assert_eq!(image.pixel(2, 2), options.visited, "The start was visited");
+        for (row, col) in solver.frontier() {
+            assert_eq!(image.pixel(col as u32 * 10 + 2, row as u32 * 10 + 2), options.frontier);
+        }
+        assert_eq!(image.pixel(2, 12), options.background, "The exit isn't visited yet");
+    }
+
+    #[test]
+    fn test_palette() {
+        let options = RenderOptions::terminal();
+
+        let palette = options.palette();
+
+        assert_eq!(palette.len(), 10, "The exit, heat and filled colours are the same red");
+        assert_eq!(palette[0], options.background);
+    }
+
+    #[test]
+    fn test_to_svg() {
+        let maze = create_test_maze();
+
+        let svg = to_svg(&Scene::new(&maze), &create_test_options());
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"32\" height=\"22\""));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert!(svg.contains("<rect x=\"0\" y=\"0\" width=\"32\" height=\"22\" fill=\"#ffffff\"/>"));
+        assert_eq!(svg.matches("fill=\"#000000\"").count(), 12, "Every closed wall is drawn once");
+    }
+
+    #[test]
+    fn test_picture_size() {
+        let options = create_test_options();
+        assert_eq!(picture_size(&create_test_maze(), &options), Some((32, 22)));
+
+        let huge = RenderOptions { cell_size: 1_000_000, ..options };
+        assert_eq!(picture_size(&Maze::new(5000, 5), &huge), None, "The width doesn't fit in a u32");
+        assert_eq!(picture_size(&Maze::new(2000, 2000), &RenderOptions::default()), None, "The picture has too many pixels");
+    }
+
+    #[test]
+    fn test_visit_counts() {
+        let mut maze = create_test_maze();
+        maze.open_adjacent_wall((0, 0), (1, 0));
+        maze.exit = (1, 1);
+        let maze = Rc::new(RefCell::new(maze));
+        let mut solver = solver::create("right-hand", maze, &SolverOptions::default()).unwrap();
+
+        let counts = visit_counts(solver.as_mut(), 100);
+
+        assert_eq!(counts, vec![vec![1, 1, 1], vec![0, 1, 1]]);
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_write_png() {
+        let maze = create_test_maze();
+        let image = rasterize(&Scene::new(&maze), &create_test_options());
+        let mut png = Vec::new();
+
+        image.write_png(&mut png).unwrap();
+
+        let mut reader = png::Decoder::new(png.as_slice()).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((info.width, info.height), (32, 22));
+        assert_eq!(&pixels[..info.buffer_size()], image.pixels.as_slice());
+    }
+}
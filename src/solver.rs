@@ -0,0 +1,299 @@
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::rc::Rc;
+use crate::astar::{AStar, Heuristic};
+use crate::bfs::Bfs;
+use crate::dijkstra::Dijkstra;
+use crate::filling::{CulDeSacFilling, DeadEndFilling};
+use crate::direction::Direction;
+use crate::maze::Maze;
+use crate::pledge::Pledge;
+use crate::tremaux::Tremaux;
+use crate::wall_follower::{Hand, WallFollower};
+
+/// Names of every solver that can be picked from the CLI or cycled in the TUI.
+pub const SOLVERS: [&str; 9] = [
+    "right-hand",
+    "left-hand",
+    "pledge",
+    "tremaux",
+    "bfs",
+    "astar",
+    "dijkstra",
+    "dead-end-filling",
+    "cul-de-sac-filling",
+];
+
+/// Settings shared by every solver, ignored by the ones they don't apply to.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SolverOptions {
+    pub heuristic: Heuristic,
+}
+
+/// Steps allowed per cell of the maze before `automatic_execution` gives up.
+pub const STEP_BUDGET_PER_CELL: usize = 8;
+
+/// How a solver run ended.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "kebab-case"))]
+pub enum SolveOutcome {
+    /// The solver reached the exit.
+    Reached,
+    /// The solver proved it can't reach the exit, e.g. a wall follower looping around an island.
+    Unreachable,
+    /// The solver was stopped before reaching a conclusion.
+    BudgetExceeded,
+}
+
+impl SolveOutcome {
+    pub fn description(&self) -> &'static str {
+        match self {
+            SolveOutcome::Reached => "Exit reached",
+            SolveOutcome::Unreachable => "Exit unreachable",
+            SolveOutcome::BudgetExceeded => "Step budget exceeded",
+        }
+    }
+
+    /// Short name of the outcome, used in machine readable output.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SolveOutcome::Reached => "reached",
+            SolveOutcome::Unreachable => "unreachable",
+            SolveOutcome::BudgetExceeded => "budget-exceeded",
+        }
+    }
+}
+
+/// Passage between two adjacent cells.
+pub type Passage = ((usize, usize), (usize, usize));
+
+/// Key of the passage between `first` and `second`, the same both ways.
+pub fn passage(first: (usize, usize), second: (usize, usize)) -> Passage {
+    if first <= second { (first, second) } else { (second, first) }
+}
+
+/// Cell each cell was reached from during a search, stored as the direction leading back
+/// to it so that it takes a byte per cell of the maze.
+#[derive(Clone, Debug)]
+pub struct Parents {
+    width: usize,
+    directions: Vec<Option<Direction>>,
+}
+
+impl Parents {
+    pub fn new(maze: &Maze) -> Self {
+        Parents { width: maze.width, directions: vec![None; maze.width * maze.height] }
+    }
+
+    pub fn get(&self, cell: (usize, usize)) -> Option<(usize, usize)> {
+        self.directions[cell.0 * self.width + cell.1]?.offset(cell)
+    }
+
+    /// Records that `cell` was reached from the adjacent cell `parent`.
+    pub fn insert(&mut self, cell: (usize, usize), parent: (usize, usize)) {
+        self.directions[cell.0 * self.width + cell.1] = Direction::between(cell, parent);
+    }
+
+    pub fn clear(&mut self) {
+        self.directions.fill(None);
+    }
+
+    /// Cells from the first one without a parent up to `to`.
+    pub fn path_to(&self, to: (usize, usize)) -> Vec<(usize, usize)> {
+        let mut path = vec![to];
+        while let Some(parent) = self.get(*path.last().unwrap()) {
+            path.push(parent);
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Default number of steps a solver gets to solve `maze`.
+pub fn step_budget(maze: &Maze) -> usize {
+    STEP_BUDGET_PER_CELL * maze.width * maze.height
+}
+
+/// Copies of solvers, implemented for every solver that is `Clone`.
+pub trait SolverClone {
+    /// Copy of the solver in its current state, sharing its maze.
+    fn clone_box(&self) -> Box<dyn Solver>;
+}
+
+impl<T: Solver + Clone + 'static> SolverClone for T {
+    fn clone_box(&self) -> Box<dyn Solver> {
+        Box::new(self.clone())
+    }
+}
+
+/// A step-by-step maze solving algorithm.
+///
+/// Solvers work on a shared maze: the position of the solver is stored in
+/// `Maze::current_cell` and the cells it went through are flagged with `Cell::visited`.
+pub trait Solver: Debug + SolverClone {
+    fn name(&self) -> &'static str;
+
+    /// Name displayed to the user, including the settings the solver runs with.
+    fn label(&self) -> String {
+        self.name().to_string()
+    }
+
+    fn maze(&self) -> &Rc<RefCell<Maze>>;
+
+    /// Advances the algorithm by a single iteration.
+    fn step(&mut self);
+
+    /// How the run ended, `None` while the solver can still make progress.
+    fn outcome(&self) -> Option<SolveOutcome>;
+
+    fn is_finished(&self) -> bool {
+        self.outcome().is_some()
+    }
+
+    /// Puts the solver and the maze back in their initial state.
+    fn reset(&mut self);
+
+    fn current_cell(&self) -> (usize, usize) {
+        self.maze().borrow().current_cell
+    }
+
+    /// Direction the solver is facing, for algorithms that walk through the maze.
+    fn direction(&self) -> Option<Direction> {
+        None
+    }
+
+    /// Cells waiting to be explored, for algorithms that keep a frontier.
+    fn frontier(&self) -> Vec<(usize, usize)> {
+        Vec::new()
+    }
+
+    /// Whether `cell` is in the frontier, looked up without listing the whole frontier.
+    fn in_frontier(&self, _cell: (usize, usize)) -> bool {
+        false
+    }
+
+    /// g and f scores of `cell`, for algorithms that compute them.
+    fn scores(&self, _cell: (usize, usize)) -> Option<(usize, f64)> {
+        None
+    }
+
+    /// Marks left on the passage between two cells, for algorithms that mark them.
+    fn passage_marks(&self) -> Vec<(Passage, u8)> {
+        Vec::new()
+    }
+
+    /// Marks left on the passage between `first` and `second`, `0` if it has none.
+    fn marks(&self, _first: (usize, usize), _second: (usize, usize)) -> u8 {
+        0
+    }
+
+    /// Path found from the start to the exit, empty until the algorithm knows it.
+    fn path(&self) -> Vec<(usize, usize)> {
+        Vec::new()
+    }
+
+    /// Runs the solver until it is finished, within the default step budget.
+    fn automatic_execution(&mut self) -> SolveOutcome {
+        let budget = step_budget(&self.maze().borrow());
+        self.execute_with_budget(budget)
+    }
+
+    /// Runs the solver until it is finished or `budget` steps were taken.
+    fn execute_with_budget(&mut self, budget: usize) -> SolveOutcome {
+        for _ in 0..budget {
+            if let Some(outcome) = self.outcome() {
+                return outcome;
+            }
+            self.step();
+        }
+        self.outcome().unwrap_or(SolveOutcome::BudgetExceeded)
+    }
+}
+
+pub fn create(name: &str, maze: Rc<RefCell<Maze>>, options: &SolverOptions) -> Option<Box<dyn Solver>> {
+    match name {
+        "right-hand" => Some(Box::new(WallFollower::new(maze, Hand::Right))),
+        "left-hand" => Some(Box::new(WallFollower::new(maze, Hand::Left))),
+        "pledge" => Some(Box::new(Pledge::new(maze))),
+        "tremaux" => Some(Box::new(Tremaux::new(maze))),
+        "bfs" => Some(Box::new(Bfs::new(maze))),
+        "astar" => Some(Box::new(AStar::new(maze, options.heuristic))),
+        "dijkstra" => Some(Box::new(Dijkstra::new(maze))),
+        "dead-end-filling" => Some(Box::new(DeadEndFilling::new(maze))),
+        "cul-de-sac-filling" => Some(Box::new(CulDeSacFilling::new(maze))),
+        _ => None,
+    }
+}
+
+/// Returns the solver following `name` in `SOLVERS`, wrapping around at the end.
+pub fn next_name(name: &str) -> &'static str {
+    let position = SOLVERS.iter().position(|solver| *solver == name).unwrap_or(0);
+    SOLVERS[(position + 1) % SOLVERS.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_known_solvers() {
+        let maze = Rc::new(RefCell::new(Maze::new(3, 3)));
+
+        for name in SOLVERS {
+            let solver = create(name, maze.clone(), &SolverOptions::default()).expect("Every listed solver should be creatable");
+            assert_eq!(solver.name(), name);
+        }
+    }
+
+    #[test]
+    fn test_create_unknown_solver() {
+        let maze = Rc::new(RefCell::new(Maze::new(3, 3)));
+
+        assert!(create("unknown", maze, &SolverOptions::default()).is_none());
+    }
+
+    #[test]
+    fn test_cell_queries_match_frontier_and_marks() {
+        let maze = Maze::generate_with(&mut crate::generator::kruskal::Kruskal::default(), 8, 6, 4);
+
+        for name in SOLVERS {
+            let maze = Rc::new(RefCell::new(maze.clone()));
+            let mut solver = create(name, maze.clone(), &SolverOptions::default()).unwrap();
+
+            while !solver.is_finished() {
+                let frontier = solver.frontier();
+                let marks = solver.passage_marks();
+                let cells: Vec<_> = maze.borrow().cells().map(|(cell, _)| cell).collect();
+                for cell in cells {
+                    assert_eq!(solver.in_frontier(cell), frontier.contains(&cell), "{}: wrong frontier state for {:?}", name, cell);
+                }
+                for &((first, second), count) in &marks {
+                    assert_eq!(solver.marks(first, second), count, "{}: wrong marks between {:?} and {:?}", name, first, second);
+                }
+                solver.step();
+            }
+        }
+    }
+
+    #[test]
+    fn test_parents_path() {
+        let mut parents = Parents::new(&Maze::new(3, 2));
+        parents.insert((0, 1), (0, 0));
+        parents.insert((1, 1), (0, 1));
+        parents.insert((1, 2), (1, 1));
+
+        assert_eq!(parents.get((1, 1)), Some((0, 1)));
+        assert_eq!(parents.get((0, 0)), None);
+        assert_eq!(parents.path_to((1, 2)), vec![(0, 0), (0, 1), (1, 1), (1, 2)]);
+
+        parents.clear();
+        assert_eq!(parents.path_to((1, 2)), vec![(1, 2)]);
+    }
+
+    #[test]
+    fn test_next_name_wraps_around() {
+        let last = SOLVERS[SOLVERS.len() - 1];
+
+        assert_eq!(next_name(last), SOLVERS[0]);
+    }
+}
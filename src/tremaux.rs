@@ -0,0 +1,262 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use crate::direction::Direction;
+use crate::maze::Maze;
+use crate::solver::{passage, Parents, Passage, SolveOutcome, Solver};
+
+/// Trémaux's algorithm: every passage is marked each time it is walked through, and
+/// is never walked through more than twice. Unlike wall followers, it solves mazes
+/// with loops from any start.
+///
+/// When the exit is reached, the passages marked once form a path from the start.
+#[derive(Clone, Debug)]
+pub struct Tremaux {
+    pub maze: Rc<RefCell<Maze>>,
+    /// Marks on the passages leading east and south of each cell, row by row: the
+    /// two lowest bits count the marks going east and the next two the ones going south.
+    marks: Vec<u8>,
+    width: usize,
+    /// Cell the walker came from, `None` at the start.
+    previous: Option<(usize, usize)>,
+    finished: bool,
+}
+
+impl Tremaux {
+    pub fn new(maze: Rc<RefCell<Maze>>) -> Self {
+        let (width, cells) = {
+            let maze = maze.borrow();
+            (maze.width, maze.width * maze.height)
+        };
+        Tremaux {
+            maze,
+            marks: vec![0; cells],
+            width,
+            previous: None,
+            finished: false,
+        }
+    }
+
+    /// Index in `marks` and bit shift of the passage between `first` and `second`,
+    /// `None` when they aren't adjacent.
+    fn slot(&self, first: (usize, usize), second: (usize, usize)) -> Option<(usize, u8)> {
+        let (from, to) = passage(first, second);
+        let index = from.0 * self.width + from.1;
+        match Direction::between(from, to)? {
+            Direction::East => Some((index, 0)),
+            Direction::South => Some((index, 2)),
+            _ => None,
+        }
+    }
+
+    /// Picks the passage to take from `cell`, `None` when every passage was walked twice.
+    fn next_cell(&self, maze: &Maze, cell: (usize, usize)) -> Option<(usize, usize)> {
+        let others = || maze.accessible_neighbours(cell).filter(|&neighbour| Some(neighbour) != self.previous);
+
+        // Coming through a new passage into a junction that was already visited
+        // means a loop was closed: go back the way we came.
+        if let Some(previous) = self.previous {
+            let known_junction = others().any(|neighbour| self.marks(cell, neighbour) > 0);
+            if known_junction && self.marks(cell, previous) == 1 {
+                return Some(previous);
+            }
+        }
+
+        others()
+            .filter(|&neighbour| self.marks(cell, neighbour) < 2)
+            .min_by_key(|&neighbour| self.marks(cell, neighbour))
+            .or(self.previous.filter(|&previous| self.marks(cell, previous) < 2))
+    }
+}
+
+impl Solver for Tremaux {
+    fn name(&self) -> &'static str {
+        "tremaux"
+    }
+
+    fn maze(&self) -> &Rc<RefCell<Maze>> {
+        &self.maze
+    }
+
+    fn step(&mut self) {
+        if self.finished {
+            return;
+        }
+
+        let mut maze = self.maze.borrow_mut();
+        let cell = maze.current_cell;
+        let Some(next) = self.next_cell(&maze, cell) else {
+            self.finished = true;
+            return;
+        };
+
+        if let Some((index, shift)) = self.slot(cell, next) {
+            self.marks[index] += 1 << shift;
+        }
+        self.previous = Some(cell);
+        maze.current_cell = next;
+        maze.set_visited(next, true);
+
+        if next == maze.exit {
+            self.finished = true;
+        }
+    }
+
+    fn outcome(&self) -> Option<SolveOutcome> {
+        let maze = self.maze.borrow();
+        if maze.current_cell == maze.exit {
+            Some(SolveOutcome::Reached)
+        } else if self.finished {
+            Some(SolveOutcome::Unreachable)
+        } else {
+            None
+        }
+    }
+
+    fn reset(&mut self) {
+        let mut maze = self.maze.borrow_mut();
+        maze.reset_visited_cells();
+        let start = maze.start;
+        maze.current_cell = start;
+        maze.set_visited(start, true);
+        self.marks.fill(0);
+        self.previous = None;
+        self.finished = false;
+    }
+
+    fn direction(&self) -> Option<Direction> {
+        self.previous.and_then(|previous| Direction::between(previous, self.current_cell()))
+    }
+
+    fn passage_marks(&self) -> Vec<(Passage, u8)> {
+        let mut marks = Vec::new();
+        for (index, &cell_marks) in self.marks.iter().enumerate() {
+            let cell = (index / self.width, index % self.width);
+            for (shift, direction) in [(0, Direction::East), (2, Direction::South)] {
+                let count = (cell_marks >> shift) & 0b11;
+                if let Some(next) = direction.offset(cell).filter(|_| count > 0) {
+                    marks.push(((cell, next), count));
+                }
+            }
+        }
+        marks
+    }
+
+    fn marks(&self, first: (usize, usize), second: (usize, usize)) -> u8 {
+        self.slot(first, second).map_or(0, |(index, shift)| (self.marks[index] >> shift) & 0b11)
+    }
+
+    fn path(&self) -> Vec<(usize, usize)> {
+        let maze = self.maze.borrow();
+        if maze.current_cell != maze.exit {
+            return Vec::new();
+        }
+
+        // Breadth-first search over the passages marked once
+        let mut parents = Parents::new(&maze);
+        let mut queue = VecDeque::from([maze.start]);
+        while let Some(cell) = queue.pop_front() {
+            if cell == maze.exit {
+                break;
+            }
+            for neighbour in maze.accessible_neighbours(cell) {
+                if self.marks(cell, neighbour) == 1 && neighbour != maze.start && parents.get(neighbour).is_none() {
+                    parents.insert(neighbour, cell);
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+
+        let path = parents.path_to(maze.exit);
+        if path.first() != Some(&maze.start) {
+            return Vec::new();
+        }
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wall_follower::{Hand, WallFollower};
+
+    /// 5x4 maze with a loop around the pillar at (2,2), the start on the loop and
+    /// a branch going north from (1,2) towards the exit in the top-right corner.
+    fn create_test_maze() -> Rc<RefCell<Maze>> {
+        let mut maze = Maze::new(5, 4);
+        maze.open_adjacent_wall((1, 1), (1, 2));
+        maze.open_adjacent_wall((1, 2), (1, 3));
+        maze.open_adjacent_wall((1, 3), (2, 3));
+        maze.open_adjacent_wall((2, 3), (3, 3));
+        maze.open_adjacent_wall((3, 3), (3, 2));
+        maze.open_adjacent_wall((3, 2), (3, 1));
+        maze.open_adjacent_wall((3, 1), (2, 1));
+        maze.open_adjacent_wall((2, 1), (1, 1));
+        maze.open_adjacent_wall((1, 2), (0, 2));
+        maze.open_adjacent_wall((0, 2), (0, 3));
+        maze.open_adjacent_wall((0, 3), (0, 4));
+        maze.start = (1, 1);
+        maze.current_cell = (1, 1);
+        maze.exit = (0, 4);
+
+        Rc::new(RefCell::new(maze))
+    }
+
+    #[test]
+    fn test_tremaux_solves_loops() {
+        let maze = create_test_maze();
+        let mut right_hand = WallFollower::new(maze.clone(), Hand::Right);
+        right_hand.reset();
+        assert_eq!(right_hand.automatic_execution(), SolveOutcome::Unreachable);
+
+        let mut solver = Tremaux::new(maze.clone());
+        solver.reset();
+
+        assert_eq!(solver.automatic_execution(), SolveOutcome::Reached);
+        assert_eq!(maze.borrow().current_cell, (0, 4));
+    }
+
+    #[test]
+    fn test_tremaux_marks_at_most_twice() {
+        let maze = create_test_maze();
+        maze.borrow_mut().exit = (3, 4);
+        let mut solver = Tremaux::new(maze.clone());
+        solver.reset();
+
+        assert_eq!(solver.automatic_execution(), SolveOutcome::Unreachable);
+        let marks = solver.passage_marks();
+        assert!(marks.iter().all(|&(_, count)| count == 2), "Every passage should be walked both ways");
+        assert_eq!(marks.len(), 11, "Every passage should be explored");
+        assert_eq!(maze.borrow().current_cell, (1, 1), "The walker should end up back at the start");
+    }
+
+    #[test]
+    fn test_tremaux_path_uses_single_marks() {
+        let maze = create_test_maze();
+        let mut solver = Tremaux::new(maze.clone());
+        solver.reset();
+
+        solver.automatic_execution();
+        let path = solver.path();
+
+        assert_eq!(path.first(), Some(&(1, 1)));
+        assert_eq!(path.last(), Some(&(0, 4)));
+        for window in path.windows(2) {
+            assert_eq!(solver.marks(window[0], window[1]), 1);
+        }
+    }
+
+    #[test]
+    fn test_tremaux_reset() {
+        let maze = create_test_maze();
+        let mut solver = Tremaux::new(maze.clone());
+
+        solver.automatic_execution();
+        solver.reset();
+
+        assert_eq!(maze.borrow().current_cell, (1, 1));
+        assert!(solver.passage_marks().is_empty());
+        assert_eq!(solver.direction(), None);
+        assert!(!solver.is_finished());
+    }
+}
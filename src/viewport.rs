@@ -0,0 +1,253 @@
+use crate::maze::Maze;
+
+/// Dots per cell side of the window drawn when the whole maze doesn't fit, enough to tell the walls apart.
+pub const DEFAULT_ZOOM: usize = 2;
+/// Largest zoom, in dots per cell side.
+pub const MAX_ZOOM: usize = 16;
+
+/// Rectangle of cells shown at once, so that drawing a large maze only costs the cells on screen.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Viewport {
+    pub top: usize,
+    pub left: usize,
+    pub rows: usize,
+    pub columns: usize,
+}
+
+impl Viewport {
+    /// Viewport showing every cell of the maze.
+    pub fn whole(maze: &Maze) -> Self {
+        Viewport { top: 0, left: 0, rows: maze.height, columns: maze.width }
+    }
+
+    /// Window of at most `rows` by `columns` cells, centred on `center` unless that would cross the maze edges.
+    pub fn around(maze: &Maze, center: (usize, usize), rows: usize, columns: usize) -> Self {
+        let rows = rows.clamp(1, maze.height);
+        let columns = columns.clamp(1, maze.width);
+
+        Viewport {
+            top: center.0.saturating_sub(rows / 2).min(maze.height - rows),
+            left: center.1.saturating_sub(columns / 2).min(maze.width - columns),
+            rows,
+            columns,
+        }
+    }
+
+    pub fn bottom(&self) -> usize {
+        self.top + self.rows
+    }
+
+    pub fn right(&self) -> usize {
+        self.left + self.columns
+    }
+
+    pub fn contains(&self, (row, column): (usize, usize)) -> bool {
+        (self.top..self.bottom()).contains(&row) && (self.left..self.right()).contains(&column)
+    }
+
+    /// Cells inside the viewport, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> {
+        let (left, right) = (self.left, self.right());
+        (self.top..self.bottom()).flat_map(move |row| (left..right).map(move |column| (row, column)))
+    }
+}
+
+/// Part of the maze shown in an area of dots, such as the braille dots of a terminal canvas:
+/// a zoom level, and the cell the viewport is centred on.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Camera {
+    /// Dots per cell side, `0` fitting the whole maze in the area.
+    pub zoom: usize,
+    /// Centre of the viewport when it was panned by hand.
+    pub center: (usize, usize),
+    /// Whether the viewport stays centred on the followed cell rather than on `center`.
+    pub follow: bool,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Camera { zoom: 0, center: (0, 0), follow: true }
+    }
+}
+
+impl Camera {
+    /// Cells shown in an area of `dots`, as (columns, rows), `focus` being the followed cell.
+    ///
+    /// A maze with more cells than the area has dots can't be fitted, it falls back to a
+    /// window at `DEFAULT_ZOOM` so that only the cells on screen are drawn.
+    pub fn viewport(&self, maze: &Maze, dots: (usize, usize), focus: (usize, usize)) -> Viewport {
+        let zoom = match self.zoom {
+            0 if fit_zoom(maze, dots) > 0 => return Viewport::whole(maze),
+            0 => DEFAULT_ZOOM,
+            zoom => zoom,
+        };
+        let center = if self.follow { focus } else { self.center };
+        Viewport::around(maze, center, dots.1 / zoom, dots.0 / zoom)
+    }
+
+    /// Doubles the dots per cell, starting from the first power of two larger than the fitted maze.
+    pub fn zoom_in(&mut self, maze: &Maze, dots: (usize, usize)) {
+        self.zoom = match self.zoom {
+            0 => match fit_zoom(maze, dots) {
+                0 => DEFAULT_ZOOM * 2,
+                fit => (fit + 1).next_power_of_two(),
+            },
+            zoom => zoom * 2,
+        }
+        .min(MAX_ZOOM);
+
+        if self.zoom <= fit_zoom(maze, dots) {
+            self.zoom = 0;
+        }
+    }
+
+    /// Halves the dots per cell, going back to the whole maze once it fits.
+    pub fn zoom_out(&mut self, maze: &Maze, dots: (usize, usize)) {
+        let fit = fit_zoom(maze, dots);
+        self.zoom = match self.zoom / 2 {
+            0 if fit == 0 => self.zoom,
+            zoom if zoom <= fit => 0,
+            zoom => zoom,
+        };
+    }
+
+    /// Moves `viewport` by `rows` and `columns` cells without crossing the maze edges, and stops following.
+    pub fn pan(&mut self, maze: &Maze, viewport: Viewport, (rows, columns): (isize, isize)) {
+        let top = viewport.top.saturating_add_signed(rows).min(maze.height - viewport.rows);
+        let left = viewport.left.saturating_add_signed(columns).min(maze.width - viewport.columns);
+
+        self.center = (top + viewport.rows / 2, left + viewport.columns / 2);
+        self.follow = false;
+    }
+
+    /// Centres the viewport on `cell`, and stops following.
+    pub fn center_on(&mut self, cell: (usize, usize)) {
+        self.center = cell;
+        self.follow = false;
+    }
+
+    /// Starts or stops following, staying on `focus` when it stops.
+    pub fn toggle_follow(&mut self, focus: (usize, usize)) {
+        if self.follow {
+            self.center = focus;
+        }
+        self.follow = !self.follow;
+    }
+}
+
+/// Largest number of dots per cell side at which the whole maze fits in `dots`, `0` if it doesn't fit at all.
+fn fit_zoom(maze: &Maze, dots: (usize, usize)) -> usize {
+    (dots.0 / maze.width).min(dots.1 / maze.height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whole() {
+        let maze = Maze::new(7, 4);
+        let viewport = Viewport::whole(&maze);
+
+        assert_eq!(viewport.cells().count(), 28);
+        assert!(viewport.contains((3, 6)));
+        assert!(!viewport.contains((4, 0)));
+    }
+
+    #[test]
+    fn test_around_stays_inside_the_maze() {
+        let maze = Maze::new(100, 50);
+
+        let centred = Viewport::around(&maze, (25, 50), 10, 20);
+        assert_eq!(centred, Viewport { top: 20, left: 40, rows: 10, columns: 20 });
+
+        let corner = Viewport::around(&maze, (0, 99), 10, 20);
+        assert_eq!(corner, Viewport { top: 0, left: 80, rows: 10, columns: 20 });
+        assert!(corner.contains((0, 99)));
+
+        let larger = Viewport::around(&maze, (10, 10), 500, 500);
+        assert_eq!(larger, Viewport::whole(&maze));
+    }
+
+    #[test]
+    fn test_cells_are_row_by_row() {
+        let maze = Maze::new(10, 10);
+        let viewport = Viewport::around(&maze, (5, 5), 2, 3);
+
+        let cells: Vec<_> = viewport.cells().collect();
+        assert_eq!(cells, vec![(4, 4), (4, 5), (4, 6), (5, 4), (5, 5), (5, 6)]);
+    }
+
+    #[test]
+    fn test_camera_fits_small_mazes() {
+        let maze = Maze::new(20, 10);
+        let camera = Camera::default();
+
+        assert_eq!(camera.viewport(&maze, (100, 80), (5, 5)), Viewport::whole(&maze));
+        assert_eq!(camera.viewport(&maze, (10, 80), (5, 5)), Viewport { top: 0, left: 3, rows: 10, columns: 5 });
+    }
+
+    #[test]
+    fn test_camera_zoom() {
+        let maze = Maze::new(20, 10);
+        let dots = (100, 80);
+        let mut camera = Camera::default();
+
+        // The whole maze fits at 5 dots per cell
+        camera.zoom_in(&maze, dots);
+        assert_eq!(camera.zoom, 8);
+        assert_eq!(camera.viewport(&maze, dots, (0, 0)), Viewport { top: 0, left: 0, rows: 10, columns: 12 });
+
+        camera.zoom_in(&maze, dots);
+        camera.zoom_in(&maze, dots);
+        assert_eq!(camera.zoom, MAX_ZOOM);
+
+        camera.zoom_out(&maze, dots);
+        assert_eq!(camera.zoom, 8);
+        camera.zoom_out(&maze, dots);
+        assert_eq!(camera.zoom, 0, "Zooming out to a scale the maze fits at should show the whole maze");
+    }
+
+    #[test]
+    fn test_camera_zoom_on_large_mazes() {
+        let maze = Maze::new(1000, 1000);
+        let dots = (100, 80);
+        let mut camera = Camera::default();
+
+        assert_eq!(camera.viewport(&maze, dots, (500, 500)).columns, 100 / DEFAULT_ZOOM);
+
+        camera.zoom_out(&maze, dots);
+        camera.zoom_out(&maze, dots);
+        camera.zoom_out(&maze, dots);
+        assert_eq!(camera.zoom, 0);
+
+        camera.zoom = 2;
+        camera.zoom_out(&maze, dots);
+        camera.zoom_out(&maze, dots);
+        assert_eq!(camera.zoom, 1, "A maze that can't be fitted should stop at one dot per cell");
+        assert_eq!(camera.viewport(&maze, dots, (500, 500)), Viewport { top: 460, left: 450, rows: 80, columns: 100 });
+    }
+
+    #[test]
+    fn test_camera_follow_and_pan() {
+        let maze = Maze::new(100, 100);
+        let dots = (20, 20);
+        let mut camera = Camera { zoom: 2, ..Camera::default() };
+
+        let viewport = camera.viewport(&maze, dots, (50, 50));
+        assert_eq!(viewport, Viewport { top: 45, left: 45, rows: 10, columns: 10 });
+
+        camera.pan(&maze, viewport, (3, -100));
+        assert!(!camera.follow);
+        assert_eq!(camera.viewport(&maze, dots, (50, 50)), Viewport { top: 48, left: 0, rows: 10, columns: 10 });
+
+        camera.toggle_follow((50, 50));
+        assert_eq!(camera.viewport(&maze, dots, (99, 99)), Viewport { top: 90, left: 90, rows: 10, columns: 10 });
+
+        camera.toggle_follow((99, 99));
+        assert_eq!(camera.center, (99, 99), "Stopping to follow should keep the viewport where it is");
+
+        camera.center_on((20, 30));
+        assert_eq!(camera.viewport(&maze, dots, (99, 99)), Viewport { top: 15, left: 25, rows: 10, columns: 10 });
+    }
+}
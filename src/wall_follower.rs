@@ -0,0 +1,261 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::direction::Direction;
+use crate::maze::Maze;
+use crate::solver::{SolveOutcome, Solver};
+
+/// Side of the walker kept against the wall.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+impl Hand {
+    /// Directions to try from `direction`, in order of preference: towards the hand,
+    /// straight ahead, away from the hand and finally back.
+    pub fn preferences(self, direction: Direction) -> [Direction; 4] {
+        match self {
+            Hand::Right => [direction.turn_right(), direction, direction.turn_left(), direction.opposite()],
+            Hand::Left => [direction.turn_left(), direction, direction.turn_right(), direction.opposite()],
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct WallFollower {
+    pub maze: Rc<RefCell<Maze>>,
+    pub hand: Hand,
+    pub current_direction: Direction,
+    /// Directions the walker has been facing in each cell, one bit per direction and
+    /// a byte per cell. Being in one of those states again means it is looping without
+    /// ever reaching the exit.
+    states: Vec<u8>,
+    looping: bool,
+}
+
+impl WallFollower {
+    pub fn new(maze: Rc<RefCell<Maze>>, hand: Hand) -> Self {
+        let states = {
+            let maze = maze.borrow();
+            let mut states = vec![0; maze.width * maze.height];
+            states[maze.index(maze.start)] = direction_bit(Direction::North);
+            states
+        };
+        WallFollower {
+            maze,
+            hand,
+            current_direction: Direction::North,
+            states,
+            looping: false,
+        }
+    }
+}
+
+/// Bit of `direction` in `WallFollower::states`.
+fn direction_bit(direction: Direction) -> u8 {
+    match direction {
+        Direction::North => 1,
+        Direction::East => 2,
+        Direction::South => 4,
+        Direction::West => 8,
+    }
+}
+
+impl Solver for WallFollower {
+    fn name(&self) -> &'static str {
+        match self.hand {
+            Hand::Left => "left-hand",
+            Hand::Right => "right-hand",
+        }
+    }
+
+    fn maze(&self) -> &Rc<RefCell<Maze>> {
+        &self.maze
+    }
+
+    fn step(&mut self) {
+        let mut maze = self.maze.borrow_mut();
+        let (next_cell, next_direction) = get_next_cell(&maze, self.current_direction, self.hand);
+        maze.current_cell = next_cell;
+        maze.set_visited(next_cell, true);
+        self.current_direction = next_direction;
+
+        let state = &mut self.states[maze.index(next_cell)];
+        if *state & direction_bit(next_direction) != 0 {
+            self.looping = true;
+        }
+        *state |= direction_bit(next_direction);
+    }
+
+    fn outcome(&self) -> Option<SolveOutcome> {
+        let maze = self.maze.borrow();
+        if maze.current_cell == maze.exit {
+            Some(SolveOutcome::Reached)
+        } else if self.looping {
+            Some(SolveOutcome::Unreachable)
+        } else {
+            None
+        }
+    }
+
+    fn reset(&mut self) {
+        let mut maze = self.maze.borrow_mut();
+        maze.reset_visited_cells();
+        let start = maze.start;
+        maze.current_cell = start;
+        maze.set_visited(start, true);
+        self.current_direction = Direction::North;
+        self.states.fill(0);
+        self.states[maze.index(start)] = direction_bit(Direction::North);
+        self.looping = false;
+    }
+
+    fn direction(&self) -> Option<Direction> {
+        Some(self.current_direction)
+    }
+}
+
+/// Moves one cell from the current cell, keeping `hand` on the wall. Stays in place
+/// when the current cell is closed on every side.
+fn get_next_cell(maze: &Maze, current_direction: Direction, hand: Hand) -> ((usize, usize), Direction) {
+    let cell = maze.current_cell;
+    hand.preferences(current_direction)
+        .into_iter()
+        .find_map(|direction| direction.step_from(maze, cell).map(|next| (next, direction)))
+        .unwrap_or((cell, current_direction))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_maze() -> Rc<RefCell<Maze>> {
+        let mut maze = Maze::new(3, 3);
+
+        // Remove walls to create a simple path
+        maze.open_adjacent_wall((0, 0), (0, 1));
+        maze.open_adjacent_wall((0, 1), (1, 1));
+        maze.open_adjacent_wall((1, 1), (1, 2));
+        maze.open_adjacent_wall((1, 2), (2, 2));
+
+        Rc::new(RefCell::new(maze))
+    }
+
+    #[test]
+    fn test_right_hand_initialization() {
+        let maze = create_test_maze();
+        let solver = WallFollower::new(maze.clone(), Hand::Right);
+
+        assert_eq!(solver.current_direction, Direction::North);
+    }
+
+    #[test]
+    fn test_right_hand_step() {
+        let maze = create_test_maze();
+        let mut solver = WallFollower::new(maze.clone(), Hand::Right);
+
+        // Initial position (0,0)
+        assert_eq!(maze.borrow().current_cell, (0, 0));
+
+        solver.step();
+
+        // After one step, should move to (0,1)
+        assert_eq!(maze.borrow().current_cell, (0, 1));
+    }
+
+    #[test]
+    fn test_right_hand_execution() {
+        let maze = create_test_maze();
+        let mut solver = WallFollower::new(maze.clone(), Hand::Right);
+
+        solver.automatic_execution();
+
+        // The solver should reach the exit (2,2)
+        assert_eq!(maze.borrow().current_cell, (2, 2));
+    }
+
+    #[test]
+    fn test_get_next_cell() {
+        let maze = create_test_maze();
+        let borrowed_maze = maze.borrow();
+
+        let ((next_x, next_y), next_dir) = get_next_cell(&borrowed_maze, Direction::North, Hand::Right);
+
+        assert_eq!((next_x, next_y), (0, 1));
+        assert_eq!(next_dir, Direction::East);
+    }
+
+    #[test]
+    fn test_right_hand_island_exit() {
+        let maze = create_test_maze();
+        {
+            // Close the corridor leading to the exit, leaving it on an island
+            let mut maze = maze.borrow_mut();
+            maze.close_adjacent_wall((1, 2), (2, 2));
+        }
+        let mut solver = WallFollower::new(maze.clone(), Hand::Right);
+
+        let outcome = solver.automatic_execution();
+
+        assert_eq!(outcome, SolveOutcome::Unreachable, "Walking in circles should be detected");
+        assert_ne!(maze.borrow().current_cell, (2, 2));
+    }
+
+    #[test]
+    fn test_right_hand_budget() {
+        let maze = create_test_maze();
+        let mut solver = WallFollower::new(maze.clone(), Hand::Right);
+
+        assert_eq!(solver.execute_with_budget(1), SolveOutcome::BudgetExceeded);
+        assert_eq!(solver.execute_with_budget(10), SolveOutcome::Reached);
+    }
+
+    #[test]
+    fn test_right_hand_reset() {
+        let maze = create_test_maze();
+        let mut solver = WallFollower::new(maze.clone(), Hand::Right);
+
+        solver.automatic_execution();
+        solver.reset();
+
+        assert_eq!(maze.borrow().current_cell, (0, 0));
+        assert_eq!(solver.current_direction, Direction::North);
+        assert!(!solver.is_finished());
+        assert!(!maze.borrow().cell((2, 2)).visited, "Reset should clear the visited cells");
+    }
+
+    #[test]
+    fn test_left_hand_execution() {
+        let maze = create_test_maze();
+        let mut solver = WallFollower::new(maze.clone(), Hand::Left);
+
+        assert_eq!(solver.automatic_execution(), SolveOutcome::Reached);
+        assert_eq!(maze.borrow().current_cell, (2, 2));
+    }
+
+    #[test]
+    fn test_hands_take_different_routes() {
+        let mut maze = Maze::new(2, 2);
+        // Both sides of the square lead to the exit
+        maze.open_adjacent_wall((0, 0), (0, 1));
+        maze.open_adjacent_wall((0, 1), (1, 1));
+        maze.open_adjacent_wall((0, 0), (1, 0));
+        maze.open_adjacent_wall((1, 0), (1, 1));
+        maze.start = (1, 0);
+        maze.current_cell = (1, 0);
+        maze.exit = (0, 1);
+        let maze = Rc::new(RefCell::new(maze));
+
+        let mut right = WallFollower::new(maze.clone(), Hand::Right);
+        right.step();
+        assert_eq!(maze.borrow().current_cell, (1, 1));
+        assert_eq!(right.execute_with_budget(10), SolveOutcome::Reached);
+
+        let mut left = WallFollower::new(maze.clone(), Hand::Left);
+        left.reset();
+        left.step();
+        assert_eq!(maze.borrow().current_cell, (0, 0));
+        assert_eq!(left.execute_with_budget(10), SolveOutcome::Reached);
+    }
+}